@@ -0,0 +1,76 @@
+//! Error type returned when reading or writing a property list.
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// The error returned by the load and store methods of [`Settings`](crate::Settings).
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// 加载或保存属性列表时返回的错误。
+/// </details>
+///
+/// # Example
+/// ```
+/// use gostd_settings::{builder, Settings, SettingsError};
+/// let mut p = builder().file_type_properties().build();
+/// let err = p.load("a = 1\nb = \\u12\n".as_bytes()).unwrap_err();
+/// match err {
+///     SettingsError::Parse { line, msg } => {
+///         assert_eq!(line, 2);
+///         assert_eq!(msg, "malformed \\uXXXX encoding");
+///     }
+///     other => panic!("unexpected error: {}", other),
+/// }
+///
+/// let err = p.load_from_file("no/such/file.properties").unwrap_err();
+/// assert!(matches!(err, SettingsError::Io(_)));
+/// ```
+#[derive(Debug)]
+pub enum SettingsError {
+    /// Reading from or writing to the underlying stream failed.
+    Io(io::Error),
+    /// The input is not valid; `line` is the 1-based line the problem was found on.
+    Parse { line: usize, msg: String },
+    /// The builder's validator, or a typed getter, rejected the value of `key`.
+    Invalid { key: String, msg: String },
+    /// A required property is not set.
+    Missing { key: String },
+    /// Loading the file at `path` failed with `source`.
+    File {
+        path: PathBuf,
+        source: Box<SettingsError>,
+    },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(err) => write!(f, "{}", err),
+            SettingsError::Parse { line, msg } => write!(f, "line {}: {}", line, msg),
+            SettingsError::Invalid { key, msg } => {
+                write!(f, "invalid value for {:?}: {}", key, msg)
+            }
+            SettingsError::Missing { key } => write!(f, "missing property {:?}", key),
+            SettingsError::File { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl error::Error for SettingsError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SettingsError::Io(err) => Some(err),
+            SettingsError::File { source, .. } => Some(source.as_ref()),
+            SettingsError::Parse { .. }
+            | SettingsError::Invalid { .. }
+            | SettingsError::Missing { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for SettingsError {
+    fn from(err: io::Error) -> Self {
+        SettingsError::Io(err)
+    }
+}
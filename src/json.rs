@@ -0,0 +1,197 @@
+//! Minimal reader and writer for flat JSON objects of string values.
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Formats the pairs as a JSON object with one member per line.
+pub(crate) fn write_object(pairs: &[(String, String)]) -> String {
+    let mut out = String::from("{");
+    for (i, (k, v)) in pairs.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("\n  ");
+        write_string(k, &mut out);
+        out.push_str(": ");
+        write_string(v, &mut out);
+    }
+    if !pairs.is_empty() {
+        out.push('\n');
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Parses a JSON object whose members are strings, numbers or booleans.
+/// Numbers and booleans are returned as their literal text.
+/// Errors carry the 1-based line the parser stopped on.
+pub(crate) fn parse_object(s: &str) -> Result<Vec<(String, String)>, (usize, String)> {
+    let mut chars = s.chars().peekable();
+    object(&mut chars).map_err(|msg| {
+        let consumed = s.chars().count() - chars.count();
+        let line = 1 + s.chars().take(consumed).filter(|c| *c == '\n').count();
+        (line, msg)
+    })
+}
+
+fn object(chars: &mut Peekable<Chars>) -> Result<Vec<(String, String)>, String> {
+    let mut pairs = vec![];
+    skip_whitespace(chars);
+    expect(chars, '{')?;
+    skip_whitespace(chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+    } else {
+        loop {
+            skip_whitespace(chars);
+            let key = parse_string(chars)?;
+            skip_whitespace(chars);
+            expect(chars, ':')?;
+            skip_whitespace(chars);
+            let value = parse_value(chars)?;
+            pairs.push((key, value));
+            skip_whitespace(chars);
+            match chars.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                other => return Err(unexpected(other, "',' or '}'")),
+            }
+        }
+    }
+    skip_whitespace(chars);
+    match chars.next() {
+        None => Ok(pairs),
+        other => Err(unexpected(other, "end of input")),
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    match chars.peek() {
+        Some('"') => parse_string(chars),
+        Some(c) if *c == '-' || c.is_ascii_alphanumeric() => {
+            let mut literal = String::new();
+            while let Some(c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || "+-.".contains(*c)) {
+                    break;
+                }
+                literal.push(*c);
+                chars.next();
+            }
+            if literal == "true" || literal == "false" || is_number(&literal) {
+                Ok(literal)
+            } else {
+                Err(format!("unsupported value {:?}", literal))
+            }
+        }
+        other => Err(unexpected(other.copied(), "a string, number or boolean")),
+    }
+}
+
+/// Checks `s` against the JSON number grammar: `-? (0 | [1-9][0-9]*) (.[0-9]+)? ([eE][+-]?[0-9]+)?`.
+fn is_number(s: &str) -> bool {
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let int = match digits(s) {
+        0 => return false,
+        n if n > 1 && s.starts_with('0') => return false,
+        n => n,
+    };
+    let mut rest = &s[int..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        match digits(fraction) {
+            0 => return false,
+            n => rest = &fraction[n..],
+        }
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        match digits(exponent) {
+            0 => return false,
+            n => rest = &exponent[n..],
+        }
+    }
+    rest.is_empty()
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    expect(chars, '"')?;
+    let mut out = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(out),
+            Some('\\') => match chars.next() {
+                Some('"') => out.push('"'),
+                Some('\\') => out.push('\\'),
+                Some('/') => out.push('/'),
+                Some('b') => out.push('\x08'),
+                Some('f') => out.push('\x0c'),
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some('t') => out.push('\t'),
+                Some('u') => {
+                    let mut unit = hex4(chars)?;
+                    if (0xD800..0xDC00).contains(&unit) {
+                        // a high surrogate must be followed by an escaped low surrogate
+                        if chars.next() != Some('\\') || chars.next() != Some('u') {
+                            return Err("unpaired surrogate in \\u escape".to_owned());
+                        }
+                        let low = hex4(chars)?;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return Err("unpaired surrogate in \\u escape".to_owned());
+                        }
+                        unit = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+                    }
+                    out.push(std::char::from_u32(unit).ok_or("invalid character in \\u escape")?);
+                }
+                other => return Err(unexpected(other, "an escape character")),
+            },
+            Some(c) => out.push(c),
+            None => return Err("unterminated string".to_owned()),
+        }
+    }
+}
+
+fn hex4(chars: &mut Peekable<Chars>) -> Result<u32, String> {
+    let mut unit = 0;
+    for _ in 0..4 {
+        let digit = chars
+            .next()
+            .and_then(|c| c.to_digit(16))
+            .ok_or("malformed \\u escape")?;
+        unit = unit * 16 + digit;
+    }
+    Ok(unit)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn expect(chars: &mut Peekable<Chars>, want: char) -> Result<(), String> {
+    match chars.next() {
+        Some(c) if c == want => Ok(()),
+        other => Err(unexpected(other, &format!("'{}'", want))),
+    }
+}
+
+fn unexpected(found: Option<char>, want: &str) -> String {
+    match found {
+        Some(c) => format!("expected {}, found {:?}", want, c),
+        None => format!("expected {}, found end of input", want),
+    }
+}
@@ -34,14 +34,35 @@
 #![allow(non_upper_case_globals)]
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
-use gostd::bytes::Buffer;
-use gostd::io::{ByteWriter, StringWriter};
+mod error;
+mod json;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod toml;
+#[cfg(feature = "watch")]
+mod watch;
+
+pub use error::SettingsError;
+#[cfg(feature = "watch")]
+pub use watch::WatchHandle;
+
 use gostd::net::url::Values;
 use gostd::strings;
 use std::collections::HashMap;
+use std::env;
+use std::fmt;
 use std::fs;
-use std::io::{BufRead, BufReader, Error, Read, Write};
-use std::sync::Mutex;
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::iter::FromIterator;
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+/// Number of temporary files created by `store_to_file`, used to name them uniquely.
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
 
 /// Summary of read and write methods for management configuration files
 /// <details class="rustdoc-toggle top-doc">
@@ -56,29 +77,168 @@ pub trait Settings {
     /// </details>
     fn property(&self, key: &str) -> Option<String>;
     /// Search for attributes in this attribute list using the specified key to return multiple attributes connected by "," converted to slices.
+    /// The builder's `list_separator` replaces "," when set.
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
     /// 用指定的键在此属性列表中搜索属性，把","连接的多个属性转换为切片返回。
     /// </details>
+    ///
+    /// Whitespace around each element is trimmed, and an empty value gives an empty slice.
+    /// A separator preceded by a backslash, as written by `set_property_slice`, belongs to the element, and `\\` stands for one backslash.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("Fruits", "a, b ,c ");
+    /// p.set_property("Single", "a");
+    /// p.set_property("Empty", "");
+    /// assert_eq!(
+    ///     p.property_slice("Fruits"),
+    ///     Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+    /// );
+    /// assert_eq!(p.property_slice("Single"), Some(vec!["a".to_string()]));
+    /// assert_eq!(p.property_slice("Empty"), Some(vec![]));
+    /// ```
     fn property_slice(&self, key: &str) -> Option<Vec<String>>;
     /// Set multiple attributes for the specified key, converting multiple attribute values into a "," concatenated attribute string.
+    /// The builder's `list_separator` replaces "," when set.
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
     /// 为指定的键设置多个属性，把多个属性值转换成“，”连接的属性字符串。
     /// </details>
+    ///
+    /// Separators and backslashes inside an element are escaped with a backslash, so `property_slice` returns the elements unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let items = vec!["a,b".to_string(), "C:\\temp".to_string(), "c".to_string()];
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property_slice("Items", items.clone());
+    /// assert_eq!(p.property("Items").as_deref(), Some("a\\,b,C:\\\\temp,c"));
+    /// assert_eq!(p.property_slice("Items"), Some(items.clone()));
+    ///
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load_from_str(&p.store_to_string().unwrap()).unwrap();
+    /// assert_eq!(q.property_slice("Items"), Some(items));
+    /// ```
     fn set_property_slice(&mut self, key: &str, value: Vec<String>);
     /// Update the specified key and properties. If the key does not exist, create a new one.
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
     /// 更新指定的键和属性,如果键不存在就新建。
     /// </details>
+    ///
+    /// A value rejected by the builder's `validator` is not stored; use `try_set_property` to get the error.
     fn set_property(&mut self, key: &str, value: &str);
     /// Reads a property list (key and element pairs) from the input character stream in a simple line-oriented format.
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
     /// 从输入流读取属性列表。
     /// </details>
-    fn load(&mut self, r: impl Read) -> Result<(), Error>;
+    ///
+    /// Each line is split on the first `=` or `:`, whichever comes first, so the value keeps any further separators verbatim; only the surrounding whitespace is trimmed.
+    /// A line without any separator, or with nothing but whitespace after it, is read as a key with an empty value.
+    /// Whitespace around the key, including tabs and indentation, is trimmed, and a line whose key is empty after trimming is skipped.
+    /// Comments are only recognized at the start of a line; `#` or `//` later in a line are part of the value.
+    /// A line ending with an odd number of backslashes continues on the next line, whose leading whitespace is skipped.
+    /// Keys and values are unescaped: `\t`, `\n`, `\r`, `\f` and `\uXXXX` decode to the corresponding characters, and any other escaped character (such as `\\`, `\=` or `\:`) stands for itself.
+    /// Lines may end with `\n` or `\r\n`, in any mix, and a byte order mark at the start of the input is skipped.
+    /// A last line without a newline may still end with `\r`, which is dropped as well.
+    /// A malformed `\uXXXX` escape returns `SettingsError::Parse` with the 1-based line the entry starts on, even when it spans continuation lines.
+    /// Loading adds to the keys already in the list, overwriting those that appear again;
+    /// use [`reload_from_file`](Settings::reload_from_file) to replace the contents instead.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// let text = "MongoServer = mongodb://10.11.1.5/?replicaSet=mytest&w=majority\ntoken = YWJjZA==\nkey = a=b=c\njustakey\nTimeout : 30\nurl = http://example.com/#top\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(
+    ///     p.property("MongoServer").as_deref(),
+    ///     Some("mongodb://10.11.1.5/?replicaSet=mytest&w=majority")
+    /// );
+    /// assert_eq!(p.property("token").as_deref(), Some("YWJjZA=="));
+    /// assert_eq!(p.property("key").as_deref(), Some("a=b=c"));
+    /// assert_eq!(p.property("justakey").as_deref(), Some(""));
+    /// assert_eq!(p.property("Timeout").as_deref(), Some("30"));
+    /// assert_eq!(p.property("url").as_deref(), Some("http://example.com/#top"));
+    ///
+    /// let text = "Fruits = apple, banana, \\\n         pear, cantaloupe, \\\n         watermelon\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(
+    ///     p.property("Fruits").as_deref(),
+    ///     Some("apple, banana, pear, cantaloupe, watermelon")
+    /// );
+    ///
+    /// let text = "Tab = a\\tb\nName = Ren\\u00e9\nEquation = x\\=1\nPath = C:\\\\temp\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("Tab").as_deref(), Some("a\tb"));
+    /// assert_eq!(p.property("Name").as_deref(), Some("René"));
+    /// assert_eq!(p.property("Equation").as_deref(), Some("x=1"));
+    /// assert_eq!(p.property("Path").as_deref(), Some("C:\\temp"));
+    /// assert!(p.load("Bad = \\u12".as_bytes()).is_err());
+    ///
+    /// // errors report the line the offending entry starts on
+    /// let mut text = String::new();
+    /// for i in 1..42 {
+    ///     text.push_str(&format!("key{} = {}\n", i, i));
+    /// }
+    /// text.push_str("broken = \\\n  \\u00\n");
+    /// let err = p.load(text.as_bytes()).unwrap_err();
+    /// assert!(matches!(err, gostd_settings::SettingsError::Parse { line: 42, .. }));
+    /// assert_eq!(err.to_string(), "line 42: malformed \\uXXXX encoding");
+    ///
+    /// let text = "\tHttpPort\t=\t8081\n    LogLevel = Debug\n \t = orphan\n";
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property_names(), vec!["HttpPort", "LogLevel"]);
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug"));
+    /// assert!(!p.contains_key(""));
+    ///
+    /// let text = "Empty =   \nBare\n= value\n: other\n";
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("Empty").as_deref(), Some(""));
+    /// assert_eq!(p.property("Bare").as_deref(), Some(""));
+    /// assert_eq!(p.len(), 2);
+    /// assert_eq!(p.store_to_string().unwrap(), "Bare = \nEmpty = \n");
+    ///
+    /// // Windows, Unix and mixed line endings all read the same
+    /// let unix = "HttpPort = 8081\nLogLevel = Debug,\\\n    Info\nName = demo";
+    /// let windows = "HttpPort = 8081\r\nLogLevel = Debug,\\\r\n    Info\r\nName = demo\r";
+    /// let mixed = "HttpPort = 8081\r\nLogLevel = Debug,\\\n    Info\r\nName = demo\r\n";
+    /// for text in [unix, windows, mixed].iter() {
+    ///     let mut p = builder().file_type_properties().build();
+    ///     p.load(text.as_bytes()).unwrap();
+    ///     assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    ///     assert_eq!(p.property("LogLevel").as_deref(), Some("Debug,Info"));
+    ///     assert_eq!(p.property("Name").as_deref(), Some("demo"));
+    /// }
+    ///
+    /// // a stray `\r` on a last line without `\n` is not part of the value, in any format
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load("Key = Value\r".as_bytes()).unwrap();
+    /// assert_eq!(p.property("Key").as_deref(), Some("Value"));
+    /// let mut p = builder().file_type_properties().quoted_values().build();
+    /// p.load("Key = \"Value \"\r".as_bytes()).unwrap();
+    /// assert_eq!(p.property("Key").as_deref(), Some("Value "));
+    /// let mut p = builder().file_type_ini().build();
+    /// p.load("[server]\r\nKey = Value\r".as_bytes()).unwrap();
+    /// assert_eq!(p.property("server.Key").as_deref(), Some("Value"));
+    /// let mut p = builder().file_type_env().build();
+    /// p.load("KEY=Value\r".as_bytes()).unwrap();
+    /// assert_eq!(p.property("KEY").as_deref(), Some("Value"));
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load(&b"\xef\xbb\xbfHttpPort = 8081\nLogLevel = Debug\n"[..]).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(p.property_names(), vec!["HttpPort", "LogLevel"]);
+    /// ```
+    fn load(&mut self, r: impl Read) -> Result<(), SettingsError>;
     /// Reads a property list from a file
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
@@ -88,7 +248,7 @@ pub trait Settings {
     /// # Example
     /// ```
     /// use gostd_settings::{builder, Settings};
-    /// fn main() -> Result<(), std::io::Error> {
+    /// fn main() -> Result<(), gostd_settings::SettingsError> {
     ///     let file = "./config.properties";
     ///     let mut p = builder().file_type_properties().build();
     ///
@@ -110,19 +270,108 @@ pub trait Settings {
     /// 8081
     /// ["Debug", "Info", "Warn"]
     /// ```
-    fn load_from_file(&mut self, file_path: &str) -> Result<(), Error>;
+    ///
+    /// Any path type works, including a `PathBuf` that is not valid UTF-8.
+    ///
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let path = std::env::temp_dir().join("gostd_settings_load_from_file.properties");
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.store_to_file(&path).unwrap();
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load_from_file(&path).unwrap();
+    /// assert_eq!(q.property("HttpPort").as_deref(), Some("8081"));
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn load_from_file(&mut self, file_path: impl AsRef<Path>) -> Result<(), SettingsError>;
     /// Writes this property list (key and element pairs) in this Properties table to the output stream in a format suitable for loading into a Properties table using the Load() method.
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
     /// 将属性列表写入输出流。
     /// </details>
-    fn store(&self, w: impl Write) -> Result<(), Error>;
+    ///
+    /// Keys are written in lexicographic order, so storing the same properties always produces the same output.
+    /// When the builder enabled `preserve_order`, keys are written in insertion order instead.
+    /// Backslashes and control characters are escaped so that `load` reads them back unchanged;
+    /// keys additionally get `=`, `:` and a leading comment marker such as `#` or `!` escaped.
+    /// Leading and trailing spaces are written as `\ ` so they survive the trimming done by `load`.
+    /// Lines are streamed through a `BufWriter` rather than rendered in memory first.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("MongoServer", "mongodb://10.11.1.5");
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// let mut first = Vec::new();
+    /// let mut second = Vec::new();
+    /// p.store(&mut first).unwrap();
+    /// p.store(&mut second).unwrap();
+    /// assert_eq!(first, second);
+    /// assert_eq!(
+    ///     String::from_utf8(first).unwrap(),
+    ///     "HttpPort = 8081\nLogLevel = Debug\nMongoServer = mongodb://10.11.1.5\n"
+    /// );
+    ///
+    /// p.set_property("Banner", "a\tb = René\\");
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load(out.as_slice()).unwrap();
+    /// assert_eq!(q.property("Banner").as_deref(), Some("a\tb = René\\"));
+    ///
+    /// let cases = [
+    ///     ("multi.line", "first\nsecond\r\nthird"),
+    ///     ("a=b:c", "separators in the key"),
+    ///     ("#hash", "comment marker"),
+    ///     ("!bang", "comment marker"),
+    ///     ("back\\slash", "C:\\temp\\"),
+    ///     ("padded", "  hello  "),
+    ///     ("blank", "   "),
+    /// ];
+    /// let mut p = builder().file_type_properties().build();
+    /// for (k, v) in cases.iter() {
+    ///     p.set_property(k, v);
+    /// }
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load(out.as_slice()).unwrap();
+    /// assert_eq!(q.len(), cases.len());
+    /// for (k, v) in cases.iter() {
+    ///     assert_eq!(q.property(k).as_deref(), Some(*v));
+    /// }
+    ///
+    /// // a key starting with a comment marker is written with a backslash so it is not read as a comment
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("#weird", "1");
+    /// assert_eq!(p.store_to_string().unwrap(), "\\#weird = 1\n");
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load_from_str("\\#weird = 1\n").unwrap();
+    /// assert_eq!(q.property("#weird").as_deref(), Some("1"));
+    ///
+    /// // separators inside a key are escaped, and escaped separators are part of the key on load
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("a=b", "c:d");
+    /// let text = p.store_to_string().unwrap();
+    /// assert_eq!(text, "a\\=b = c:d\n");
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load_from_str(&text).unwrap();
+    /// assert_eq!(q.property("a=b").as_deref(), Some("c:d"));
+    /// ```
+    fn store(&self, w: impl Write) -> Result<(), SettingsError>;
     /// Writes a list of property to a file.
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
     /// 将属性列表写入文件。
     /// </details>
     ///
+    /// The list is first written to a temporary file in the same directory, which then replaces the target,
+    /// so a failed write never leaves a truncated file behind. The new file keeps the permissions of the one it replaces.
+    /// If the target is a symbolic link, the file it points to is replaced and the link is kept.
+    ///
     /// # Example
     /// ```
     ///    use gostd_settings::{Settings, builder};
@@ -140,6 +389,14 @@ pub trait Settings {
     ///        Ok(()) => println!("store to file app.conf success"),
     ///        Err(err) => println!("store to file app.conf failed: {}", err),
     ///    }
+    ///
+    ///    // a target that cannot be replaced is left untouched
+    ///    let dir = std::env::temp_dir().join("gostd_settings_store_to_file");
+    ///    std::fs::create_dir_all(dir.join("app.conf")).unwrap();
+    ///    assert!(p.store_to_file(dir.join("app.conf")).is_err());
+    ///    assert!(dir.join("app.conf").is_dir());
+    ///    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    ///    std::fs::remove_dir_all(&dir).unwrap();
     /// ```
     /// # Output
     /// ```text
@@ -148,151 +405,4116 @@ pub trait Settings {
     /// LogLevel = Debug,Info,Warn
     /// MongoServer = mongodb://10.11.1.5,10.11.1.6,10.11.1.7/?replicaSet=mytest
     /// ```
-    fn store_to_file(&self, file_path: &str) -> Result<(), Error>;
+    fn store_to_file(&self, file_path: impl AsRef<Path>) -> Result<(), SettingsError>;
     /// Returns an enumeration of all keys in the property list.
     /// <details class="rustdoc-toggle top-doc">
     /// <summary class="docblock">zh-cn</summary>
     /// 返回属性列表中所有键的枚举。
     /// </details>
+    ///
+    /// The keys are sorted, or in insertion order when the builder enabled `preserve_order`, matching `store`.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("MongoServer", "mongodb://10.11.1.5");
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// assert_eq!(p.property_names(), vec!["HttpPort", "LogLevel", "MongoServer"]);
+    ///
+    /// let mut p = builder().file_type_properties().preserve_order().build();
+    /// p.set_property("MongoServer", "mongodb://10.11.1.5");
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(p.property_names(), vec!["MongoServer", "HttpPort"]);
+    /// ```
     fn property_names(&self) -> Vec<String>;
-}
-
-pub fn builder() -> SettingsBuilder {
-    SettingsBuilder { properties: false }
-}
-
-#[derive(Default)]
-struct Properties {
-    object: Mutex<HashMap<String, String>>,
-}
-
-pub struct SettingsBuilder {
-    properties: bool,
-}
-
-impl SettingsBuilder {
-    pub fn file_type_properties(&mut self) -> Self {
-        Self { properties: true }
-    }
-    pub fn build(self) -> impl Settings {
-        if self.properties {
-            return Properties::default();
-        }
-        return Properties::default();
-    }
-}
-
-impl Properties {
-    fn line(key: &str, value: &str, buf: &mut Buffer) {
-        buf.WriteString(key);
-        buf.WriteString(" = ");
-        buf.WriteString(value);
-        buf.WriteByte(b'\n');
-    }
-
-    fn parse_line(&mut self, line: &str) {
-        let line_str = strings::TrimSpace(line);
-        if Self::is_comment_line(line_str) {
-            return;
-        }
-        let (key, value, _) = strings::Cut(line_str, "=");
-        self.set_property(strings::TrimSpace(key), strings::TrimSpace(value));
-    }
-
-    fn is_comment_line(line: &str) -> bool {
-        if line.len() == 0 {
-            return true;
-        }
-        if strings::HasPrefix(line, "#")
-            || strings::HasPrefix(line, "//")
-            || strings::HasPrefix(line, "/*")
-        {
-            return true;
-        }
-        false
-    }
-}
-
-impl Settings for Properties {
-    fn property(&self, key: &str) -> Option<String> {
-        self.object
-            .lock()
-            .unwrap()
-            .get(key)
-            .and_then(|v| Some(v.to_owned()))
-    }
-
-    fn property_slice(&self, key: &str) -> Option<Vec<String>> {
-        self.object.lock().unwrap().get(key).and_then(|value| {
-            Some(
-                strings::Split(value, ",")
-                    .iter()
-                    .map(|x| x.to_string())
-                    .collect(),
-            )
-        })
-    }
-
-    fn set_property_slice(&mut self, key: &str, values: Vec<String>) {
-        let value = strings::Join(values, ",");
-        self.set_property(key, &value);
-    }
-
-    fn set_property(&mut self, key: &str, value: &str) {
-        self.object
-            .lock()
-            .unwrap()
-            .insert(key.to_owned(), value.to_owned());
-    }
-
-    fn load(&mut self, r: impl Read) -> Result<(), Error> {
-        let mut br = BufReader::new(r);
-        let mut line = String::new();
-        loop {
-            match br.read_line(&mut line) {
-                Ok(i) => {
-                    if i == 0 {
-                        break;
-                    } else {
-                        self.parse_line(&line);
-                        line.clear();
-                    }
-                }
-                Err(err) => return Err(err),
-            }
-        }
-        Ok(())
-    }
-
-    fn load_from_file(&mut self, file_path: &str) -> Result<(), Error> {
-        let f = fs::File::open(file_path)?;
-        self.load(f)
-    }
-
-    fn store(&self, mut w: impl Write) -> Result<(), Error> {
-        let mut buf = Buffer::new();
-        self.object
-            .lock()
-            .unwrap()
-            .iter()
-            .for_each(|(k, v)| Self::line(&k, &v, &mut buf));
-        w.write(buf.Bytes().as_slice())?;
-        Ok(())
-    }
-
-    fn store_to_file(&self, file_path: &str) -> Result<(), Error> {
-        let f = fs::File::create(file_path)?;
-        self.store(f)?;
-        Ok(())
-    }
-
-    fn property_names(&self) -> Vec<String> {
-        let mut names: Vec<String> = vec![];
-        self.object
-            .lock()
-            .unwrap()
-            .iter()
-            .for_each(|(k, _)| names.push(k.to_owned()));
-        names
+    /// Removes the property with the specified key, returning its value if the key was present.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 删除指定键的属性，如果键存在则返回原来的值。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("Deprecated", "yes");
+    /// assert_eq!(p.remove_property("Deprecated").as_deref(), Some("yes"));
+    /// assert_eq!(p.property("Deprecated"), None);
+    /// assert_eq!(p.remove_property("Deprecated"), None);
+    /// ```
+    fn remove_property(&mut self, key: &str) -> Option<String>;
+    /// Returns true if the property list contains the specified key.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 判断属性列表中是否包含指定的键。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// assert!(p.contains_key("HttpPort"));
+    /// assert!(!p.contains_key("HttpsPort"));
+    /// ```
+    fn contains_key(&self, key: &str) -> bool;
+    /// Returns the number of properties in the property list.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回属性列表中属性的数量。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// p.set_property("HttpPort", "8082");
+    /// assert_eq!(p.len(), 2);
+    /// ```
+    fn len(&self) -> usize;
+    /// Returns true if the property list contains no properties.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 判断属性列表是否为空。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// assert!(p.is_empty());
+    /// p.set_property("HttpPort", "8081");
+    /// assert!(!p.is_empty());
+    /// ```
+    fn is_empty(&self) -> bool;
+    /// Searches for the property with the specified key and parses it as an integer.
+    /// Returns `None` both when the key is missing and when the value is not a valid integer.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 用指定的键搜索属性并解析为整数。键不存在或值不是合法整数时都返回`None`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", " 8081 ");
+    /// p.set_property("Host", "localhost");
+    /// assert_eq!(p.property_int("HttpPort"), Some(8081));
+    /// assert_eq!(p.property_int("Host"), None);
+    /// assert_eq!(p.property_int("Missing"), None);
+    /// ```
+    fn property_int(&self, key: &str) -> Option<i64>;
+    /// Searches for the property with the specified key and parses it as a boolean.
+    /// `true`/`yes`/`on`/`1` and `false`/`no`/`off`/`0` are recognized case-insensitively; anything else returns `None`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 用指定的键搜索属性并解析为布尔值。不区分大小写地识别`true`/`yes`/`on`/`1`和`false`/`no`/`off`/`0`，其他值返回`None`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("Debug", "TRUE");
+    /// p.set_property("Cache", "Off");
+    /// p.set_property("Gzip", "1");
+    /// p.set_property("Tls", "no");
+    /// p.set_property("Trace", "maybe");
+    /// assert_eq!(p.property_bool("Debug"), Some(true));
+    /// assert_eq!(p.property_bool("Cache"), Some(false));
+    /// assert_eq!(p.property_bool("Gzip"), Some(true));
+    /// assert_eq!(p.property_bool("Tls"), Some(false));
+    /// assert_eq!(p.property_bool("Trace"), None);
+    /// ```
+    fn property_bool(&self, key: &str) -> Option<bool>;
+    /// Searches for the property with the specified key and parses it as a floating-point number.
+    /// Scientific notation and a leading `+` are accepted; missing keys, empty and invalid values return `None`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 用指定的键搜索属性并解析为浮点数。支持科学计数法和前导`+`；键不存在、值为空或不合法时返回`None`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("Epsilon", "1e-3");
+    /// p.set_property("Ratio", "+0.75");
+    /// p.set_property("Empty", "");
+    /// assert_eq!(p.property_float("Epsilon"), Some(0.001));
+    /// assert_eq!(p.property_float("Ratio"), Some(0.75));
+    /// assert_eq!(p.property_float("Empty"), None);
+    /// assert_eq!(p.property_float("Missing"), None);
+    /// ```
+    fn property_float(&self, key: &str) -> Option<f64>;
+    /// Returns all key-value pairs, collected under a single lock, in the same order `store` writes them.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 一次加锁返回所有键值对，顺序与`store`写入的顺序相同。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Debug");
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(
+    ///     p.iter(),
+    ///     vec![
+    ///         ("HttpPort".to_string(), "8081".to_string()),
+    ///         ("LogLevel".to_string(), "Debug".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    fn iter(&self) -> Vec<(String, String)>;
+    /// Copies every property of `other` into this property list. Keys present in both take the value from `other`, so later layers override earlier ones.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 把`other`的所有属性合并到此属性列表中。两边都存在的键使用`other`的值，即后合并的覆盖先前的。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut base = builder().file_type_properties().build();
+    /// base.set_property("HttpPort", "8081");
+    /// base.set_property("LogLevel", "Info");
+    /// let mut prod = builder().file_type_properties().build();
+    /// prod.set_property("LogLevel", "Warn");
+    /// base.merge(&prod);
+    /// assert_eq!(base.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(base.property("LogLevel").as_deref(), Some("Warn"));
+    /// ```
+    fn merge(&mut self, other: &impl Settings);
+    /// Searches for the property with the specified key, returning `default` if the key is missing.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 用指定的键搜索属性，键不存在时返回`default`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(p.property_or("HttpPort", "80"), "8081");
+    /// assert_eq!(p.property_or("HttpsPort", "443"), "443");
+    /// ```
+    fn property_or(&self, key: &str, default: &str) -> String;
+    /// Searches for the multi-value property with the specified key, returning `default` if the key is missing.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 用指定的键搜索多值属性，键不存在时返回`default`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Debug,Info");
+    /// let default = vec!["Warn".to_string()];
+    /// assert_eq!(p.property_slice_or("LogLevel", default.clone()), vec!["Debug", "Info"]);
+    /// assert_eq!(p.property_slice_or("Missing", default), vec!["Warn"]);
+    /// ```
+    fn property_slice_or(&self, key: &str, default: Vec<String>) -> Vec<String>;
+    /// Writes the property list to the output stream as a flat JSON object of strings.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将属性列表以扁平的JSON字符串对象写入输出流。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load("HttpPort = 8081\nLogLevel = Debug,Info\n".as_bytes()).unwrap();
+    /// let mut out = Vec::new();
+    /// p.store_json(&mut out).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out.clone()).unwrap(),
+    ///     "{\n  \"HttpPort\": \"8081\",\n  \"LogLevel\": \"Debug,Info\"\n}\n"
+    /// );
+    ///
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load_json(out.as_slice()).unwrap();
+    /// assert_eq!(q.iter(), p.iter());
+    /// ```
+    fn store_json(&self, w: impl Write) -> Result<(), SettingsError>;
+    /// Reads properties from a flat JSON object. String, number and boolean members are accepted; numbers and booleans are stored as their literal text.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 从扁平的JSON对象读取属性。接受字符串、数字和布尔值成员，数字和布尔值按原文保存。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_json(r#"{"HttpPort": 8081, "Debug": true, "Name": "demo é"}"#.as_bytes())
+    ///     .unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(p.property("Debug").as_deref(), Some("true"));
+    /// assert_eq!(p.property("Name").as_deref(), Some("demo é"));
+    /// assert!(p.load_json(r#"{"nested": {}}"#.as_bytes()).is_err());
+    ///
+    /// // numbers follow the JSON grammar
+    /// p.load_json(r#"{"Ratio": -0.5e+3}"#.as_bytes()).unwrap();
+    /// assert_eq!(p.property("Ratio").as_deref(), Some("-0.5e+3"));
+    /// for bad in ["inf", "NaN", "01", "1.", ".5", "+1", "1e"] {
+    ///     let json = format!(r#"{{"Ratio": {}}}"#, bad);
+    ///     assert!(p.load_json(json.as_bytes()).is_err(), "{}", bad);
+    /// }
+    ///
+    /// let err = p.load_json("{\n  \"a\": \"1\",\n  \"b\": [1]\n}".as_bytes()).unwrap_err();
+    /// assert!(matches!(err, gostd_settings::SettingsError::Parse { line: 3, .. }));
+    /// ```
+    fn load_json(&mut self, r: impl Read) -> Result<(), SettingsError>;
+    /// Writes the property list to the output stream as a TOML document of top-level string keys.
+    /// Keys containing dots are quoted, so they are not read back as nested tables.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将属性列表以顶层字符串键的TOML文档写入输出流。包含点号的键会加引号，避免被读成嵌套表。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("db.host", "10.11.1.5");
+    /// p.set_property("Banner", "say \"hi\"\n");
+    /// let mut out = Vec::new();
+    /// p.store_toml(&mut out).unwrap();
+    /// let text = String::from_utf8(out).unwrap();
+    /// assert_eq!(
+    ///     text,
+    ///     "Banner = \"say \\\"hi\\\"\\n\"\nHttpPort = \"8081\"\n\"db.host\" = \"10.11.1.5\"\n"
+    /// );
+    ///
+    /// let table: toml::Table = text.parse().unwrap();
+    /// assert_eq!(table["db.host"].as_str(), Some("10.11.1.5"));
+    /// assert_eq!(table["Banner"].as_str(), Some("say \"hi\"\n"));
+    /// ```
+    fn store_toml(&self, w: impl Write) -> Result<(), SettingsError>;
+    /// Reads a property list from a string, as `load` does from a stream.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 从字符串读取属性列表，与`load`从流中读取相同。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_from_str(
+    ///     "# server
+    /// HttpPort = 8081
+    /// LogLevel = Debug,Info,Warn
+    /// ",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(p.len(), 2);
+    /// ```
+    fn load_from_str(&mut self, s: &str) -> Result<(), SettingsError>;
+    /// Reads a property list from a byte slice, as `load` does from a stream.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 从字节切片读取属性列表，与`load`从流中读取相同。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_from_bytes(b"HttpPort = 8081\nLogLevel = Debug\n").unwrap();
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug"));
+    /// ```
+    fn load_from_bytes(&mut self, b: &[u8]) -> Result<(), SettingsError>;
+    /// Returns the property list serialized as `store` would write it.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回与`store`写入内容相同的属性列表字符串。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Debug");
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(
+    ///     p.store_to_string().unwrap(),
+    ///     "HttpPort = 8081\nLogLevel = Debug\n"
+    /// );
+    /// ```
+    fn store_to_string(&self) -> Result<String, SettingsError>;
+    /// Removes all properties, including any preserved comments, so the object can be reused.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 清空所有属性（包括保留的注释），以便重复使用该对象。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// p.clear();
+    /// assert!(p.is_empty());
+    /// assert!(p.property_names().is_empty());
+    /// p.load("HttpPort = 8082".as_bytes()).unwrap();
+    /// assert_eq!(p.property_names(), vec!["HttpPort"]);
+    /// ```
+    fn clear(&mut self);
+    /// Returns the value of the key, first inserting the default value when the key is absent.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回键对应的值；键不存在时先插入默认值再返回。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(p.property_or_insert("HttpPort", "80"), "8081");
+    /// assert_eq!(p.property_or_insert("LogLevel", "Info"), "Info");
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Info"));
+    /// assert_eq!(p.len(), 2);
+    /// ```
+    fn property_or_insert(&mut self, key: &str, default: &str) -> String;
+    /// Sets all the given properties at once, taking the lock a single time.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 一次性设置所有给定的属性，只获取一次锁。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// use std::collections::HashMap;
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_properties(vec![
+    ///     ("HttpPort".to_string(), "8081".to_string()),
+    ///     ("LogLevel".to_string(), "Debug".to_string()),
+    /// ]);
+    /// let mut defaults = HashMap::new();
+    /// defaults.insert("HttpPort".to_string(), "80".to_string());
+    /// defaults.insert("Timeout".to_string(), "30".to_string());
+    /// p.set_properties(defaults);
+    /// assert_eq!(p.len(), 3);
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("80"));
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug"));
+    /// assert_eq!(p.property("Timeout").as_deref(), Some("30"));
+    /// ```
+    fn set_properties(&mut self, pairs: impl IntoIterator<Item = (String, String)>);
+    /// Returns the pairs whose key starts with the prefix, keeping the full keys, in the same order as `iter`.
+    /// The prefix is matched as given, so end it with the namespace delimiter to match only the keys under a namespace.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回键以指定前缀开头的键值对（保留完整键名），顺序与`iter`相同。前缀按原样匹配，若只需匹配某个命名空间下的键，请以命名空间分隔符结尾。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("db.host", "10.11.1.5");
+    /// p.set_property("db.port", "27017");
+    /// p.set_property("http.port", "8081");
+    /// p.set_property("dbname", "demo");
+    /// assert_eq!(
+    ///     p.properties_with_prefix("db."),
+    ///     vec![
+    ///         ("db.host".to_string(), "10.11.1.5".to_string()),
+    ///         ("db.port".to_string(), "27017".to_string()),
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     p.properties_with_prefix("http."),
+    ///     vec![("http.port".to_string(), "8081".to_string())]
+    /// );
+    /// assert!(p.properties_with_prefix("log.").is_empty());
+    /// ```
+    fn properties_with_prefix(&self, prefix: &str) -> Vec<(String, String)>;
+    /// Returns a new property list with the keys under `prefix.`, with the prefix and dot removed. The options of this list are kept.
+    /// The dot is the namespace delimiter set with `SettingsBuilder::namespace_delimiter`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回只包含`prefix.`下的键的新属性列表，键名去掉前缀和点号，并沿用当前列表的选项。点号即`SettingsBuilder::namespace_delimiter`设置的命名空间分隔符。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("db.host", "10.11.1.5");
+    /// p.set_property("db.port", "27017");
+    /// p.set_property("dbname", "demo");
+    /// p.set_property("http.port", "8081");
+    /// let db = p.sub("db");
+    /// assert_eq!(db.property_names(), vec!["host", "port"]);
+    /// assert_eq!(db.property("port").as_deref(), Some("27017"));
+    /// assert_eq!(db.store_to_string().unwrap(), "host = 10.11.1.5\nport = 27017\n");
+    /// assert!(p.sub("log").is_empty());
+    /// ```
+    fn sub(&self, prefix: &str) -> Properties;
+    /// Returns all values, in the order of the keys returned by `property_names`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回所有值，顺序与`property_names`返回的键一致。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Debug");
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("Name", "");
+    /// assert_eq!(p.values(), vec!["8081", "Debug", ""]);
+    /// assert!(p.values().iter().any(|v| v.is_empty()));
+    /// ```
+    fn values(&self) -> Vec<String>;
+    /// Loads the file, overrides it with the properties of this list and writes the result back, keeping the keys this list does not set.
+    /// The file's comments and key order are kept, with new keys added at the end. A missing file is created.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 加载文件，用当前属性列表覆盖其中的属性后写回，保留当前列表未设置的键。文件中的注释和键的顺序保持不变，新键追加在末尾。文件不存在时会创建。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let path = std::env::temp_dir().join("gostd_settings_update_file.properties");
+    /// std::fs::write(&path, "HttpPort = 8081\nLogLevel = Debug\nMongoServer = mongodb://10.11.1.5\n").unwrap();
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Warn");
+    /// p.update_file(&path).unwrap();
+    /// assert_eq!(
+    ///     std::fs::read_to_string(&path).unwrap(),
+    ///     "HttpPort = 8081\nLogLevel = Warn\nMongoServer = mongodb://10.11.1.5\n"
+    /// );
+    /// std::fs::remove_file(&path).unwrap();
+    ///
+    /// p.update_file(&path).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "LogLevel = Warn\n");
+    ///
+    /// std::fs::write(&path, "# keep me\nZone = east\nHttpPort = 8081\n").unwrap();
+    /// p.update_file(&path).unwrap();
+    /// assert_eq!(
+    ///     std::fs::read_to_string(&path).unwrap(),
+    ///     "# keep me\nZone = east\nHttpPort = 8081\nLogLevel = Warn\n"
+    /// );
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn update_file(&self, file_path: impl AsRef<Path>) -> Result<(), SettingsError>;
+    /// Compares this list with the file, returning what `store_to_file` would add, remove or change in it, sorted by key.
+    /// A missing file counts as empty.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将当前属性列表与文件比较，按键排序返回`store_to_file`会新增、删除或修改的内容。文件不存在时视为空。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Change, Settings};
+    /// let path = std::env::temp_dir().join("gostd_settings_diff_file.properties");
+    /// std::fs::write(&path, "HttpPort = 8081\nLogLevel = Debug\nName = demo\n").unwrap();
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Warn");
+    /// p.set_property("Timeout", "30");
+    /// assert_eq!(
+    ///     p.diff_file(&path).unwrap(),
+    ///     vec![
+    ///         Change::Changed {
+    ///             key: "LogLevel".to_string(),
+    ///             old: "Debug".to_string(),
+    ///             new: "Warn".to_string(),
+    ///         },
+    ///         Change::Removed {
+    ///             key: "Name".to_string(),
+    ///             value: "demo".to_string(),
+    ///         },
+    ///         Change::Added {
+    ///             key: "Timeout".to_string(),
+    ///             value: "30".to_string(),
+    ///         },
+    ///     ]
+    /// );
+    /// std::fs::remove_file(&path).unwrap();
+    /// assert_eq!(p.diff_file(&path).unwrap().len(), 3);
+    /// ```
+    fn diff_file(&self, file_path: impl AsRef<Path>) -> Result<Vec<Change>, SettingsError>;
+    /// Sets the property like `set_property`, but returns `SettingsError::Invalid` instead of ignoring a value rejected by the builder's `validator`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 与`set_property`一样设置属性，但构建器的`validator`拒绝该值时返回`SettingsError::Invalid`，而不是忽略它。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings, SettingsError};
+    /// let mut p = builder().file_type_properties().build();
+    /// assert!(p.try_set_property("HttpPort", "8081").is_ok());
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// ```
+    fn try_set_property(&mut self, key: &str, value: &str) -> Result<(), SettingsError>;
+    /// Like `property_slice`, but splits the value on the given separator instead of the builder's `list_separator`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 与`property_slice`相同，但使用指定的分隔符而不是构建器的`list_separator`拆分值。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load("Path = /usr/local/bin:/usr/bin:/bin\nLogLevel = Debug,Info\n".as_bytes())
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     p.property_slice_with("Path", ":"),
+    ///     Some(vec![
+    ///         "/usr/local/bin".to_string(),
+    ///         "/usr/bin".to_string(),
+    ///         "/bin".to_string()
+    ///     ])
+    /// );
+    /// assert_eq!(
+    ///     p.property_slice("LogLevel"),
+    ///     Some(vec!["Debug".to_string(), "Info".to_string()])
+    /// );
+    /// ```
+    fn property_slice_with(&self, key: &str, sep: &str) -> Option<Vec<String>>;
+    /// Like `set_property_slice`, but joins the elements with the given separator instead of the builder's `list_separator`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 与`set_property_slice`相同，但使用指定的分隔符而不是构建器的`list_separator`连接元素。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let dirs = vec!["/opt/a:b".to_string(), "/usr/bin".to_string()];
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property_slice_with("Path", dirs.clone(), ":");
+    /// assert_eq!(p.property("Path").as_deref(), Some("/opt/a\\:b:/usr/bin"));
+    /// assert_eq!(p.property_slice_with("Path", ":"), Some(dirs));
+    /// ```
+    fn set_property_slice_with(&mut self, key: &str, values: Vec<String>, sep: &str);
+    /// Returns where the current value of the key came from, or `None` if the key is absent.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回键当前值的来源，键不存在时返回`None`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings, Source};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load("HttpPort = 8081\nLogLevel = Debug\n".as_bytes()).unwrap();
+    /// p.set_property("LogLevel", "Warn");
+    /// p.property_or_insert("Timeout", "30");
+    /// assert_eq!(p.source_of("HttpPort"), Some(Source::File));
+    /// assert_eq!(p.source_of("LogLevel"), Some(Source::Runtime));
+    /// assert_eq!(p.source_of("Timeout"), Some(Source::Default));
+    /// assert_eq!(p.source_of("Missing"), None);
+    ///
+    /// let mut q = builder().file_type_properties().build();
+    /// q.merge(&p);
+    /// assert_eq!(q.source_of("HttpPort"), Some(Source::File));
+    /// ```
+    fn source_of(&self, key: &str) -> Option<Source>;
+    /// Loads the files in order, so that later files override keys from earlier ones.
+    /// With `skip_missing`, files that do not exist are skipped instead of failing the call.
+    /// An error is wrapped in `SettingsError::File` naming the file it came from.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 按顺序加载多个文件，后面文件中的键覆盖前面的。`skip_missing`为真时跳过不存在的文件而不是报错。
+    /// 错误包装在`SettingsError::File`中，并注明出错的文件。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings, SettingsError};
+    /// let dir = std::env::temp_dir().join("gostd_settings_load_layered");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// let base = dir.join("base.properties");
+    /// let local = dir.join("local.properties");
+    /// std::fs::write(&base, "HttpPort = 8081\nLogLevel = Info\n").unwrap();
+    /// std::fs::write(&local, "LogLevel = Debug\n").unwrap();
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// let missing = dir.join("missing.properties");
+    /// p.load_layered(&[&base, &local, &missing], true).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug"));
+    ///
+    /// match p.load_layered(&[&base, &missing], false) {
+    ///     Err(SettingsError::File { path, source }) => {
+    ///         assert_eq!(path, missing);
+    ///         assert!(matches!(*source, SettingsError::Io(_)));
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    fn load_layered<P: AsRef<Path>>(
+        &mut self,
+        files: &[P],
+        skip_missing: bool,
+    ) -> Result<(), SettingsError>;
+    /// Loads every `*.properties` file in the directory in file name order, like `load_layered`; other files are ignored.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 按文件名顺序加载目录中的所有`*.properties`文件，效果同`load_layered`；其他文件会被忽略。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let dir = std::env::temp_dir().join("gostd_settings_load_dir");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// std::fs::write(dir.join("10-base.properties"), "HttpPort = 8081\nLogLevel = Info\n").unwrap();
+    /// std::fs::write(dir.join("20-local.properties"), "LogLevel = Debug\n").unwrap();
+    /// std::fs::write(dir.join("README.txt"), "not = loaded\n").unwrap();
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_dir(&dir).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug"));
+    /// assert!(!p.contains_key("not"));
+    /// std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<(), SettingsError>;
+    /// Moves the value of `old` to `new`, along with its comments, returning whether `old` existed.
+    /// An existing `new` is overwritten; nothing changes when `old` is missing.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将`old`的值及其注释移动到`new`，返回`old`是否存在。已存在的`new`会被覆盖；`old`不存在时不做任何修改。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("MongoServer", "mongodb://10.11.1.5");
+    /// p.set_property("DatabaseUrl", "mongodb://localhost");
+    /// assert!(p.rename_key("MongoServer", "DatabaseUrl"));
+    /// assert_eq!(p.property("MongoServer"), None);
+    /// assert_eq!(p.property("DatabaseUrl").as_deref(), Some("mongodb://10.11.1.5"));
+    /// assert!(!p.rename_key("MongoServer", "DatabaseUrl"));
+    /// ```
+    fn rename_key(&mut self, old: &str, new: &str) -> bool;
+    /// Replaces `old_prefix` with `new_prefix` in every key that starts with it, returning how many keys were renamed.
+    /// Like [`rename_key`](Settings::rename_key), a renamed key overwrites an existing key of the same name.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将所有以`old_prefix`开头的键的前缀替换为`new_prefix`，返回被重命名的键的数量。与[`rename_key`](Settings::rename_key)一样，重命名后的键会覆盖已存在的同名键。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("old.db.host", "10.11.1.5");
+    /// p.set_property("old.db.port", "27017");
+    /// p.set_property("new.db.port", "3306");
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(p.rename_prefix("old.db.", "new.db."), 2);
+    /// assert_eq!(
+    ///     p.iter(),
+    ///     vec![
+    ///         ("HttpPort".to_owned(), "8081".to_owned()),
+    ///         ("new.db.host".to_owned(), "10.11.1.5".to_owned()),
+    ///         ("new.db.port".to_owned(), "27017".to_owned()),
+    ///     ]
+    /// );
+    /// ```
+    fn rename_prefix(&mut self, old_prefix: &str, new_prefix: &str) -> usize;
+    /// Reads a property list like [`load`](Settings::load), but only keeps the keys for which `keep` returns true.
+    /// Every line is still parsed, so syntax errors anywhere in the input are reported.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 与[`load`](Settings::load)一样读取属性列表，但只保留`keep`返回true的键。所有行仍会被解析，因此任何位置的语法错误都会被报告。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let text = "# database\ndb.host = 10.11.1.5\ndb.port = 27017\n# web\nHttpPort = 8081\nLogLevel = Debug\n";
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_filtered(text.as_bytes(), |key| key.starts_with("db."))
+    ///     .unwrap();
+    /// assert_eq!(p.property_names(), vec!["db.host", "db.port"]);
+    /// assert_eq!(p.property("HttpPort"), None);
+    /// ```
+    fn load_filtered(
+        &mut self,
+        r: impl Read,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<(), SettingsError>;
+    /// Sets the comment written on `#` lines above `key` by `store`, one line per line of `comment`.
+    /// It replaces any comment loaded with `preserve_comments`; an empty comment removes it.
+    /// Nothing happens when `key` is not set.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 设置`store`时写在`key`上方的`#`注释行，`comment`的每一行对应一行注释。它会替换通过`preserve_comments`加载的注释；空注释会删除注释。`key`不存在时不做任何修改。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().preserve_order().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// p.set_comment("LogLevel", "one of Debug, Info, Warn\nor Error");
+    /// assert_eq!(
+    ///     p.store_to_string().unwrap(),
+    ///     "HttpPort = 8081\n# one of Debug, Info, Warn\n# or Error\nLogLevel = Debug\n"
+    /// );
+    /// assert_eq!(p.comment("LogLevel").as_deref(), Some("one of Debug, Info, Warn\nor Error"));
+    /// ```
+    fn set_comment(&mut self, key: &str, comment: &str);
+    /// Returns the comment above `key`, without the comment prefixes (and `;` in INI files), blank lines or the space after a prefix.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回`key`上方的注释，去掉注释前缀（INI文件中还包括`;`）、空行以及前缀后的空格。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().preserve_comments().build();
+    /// p.load("# server settings\n\n! port to listen on\nHttpPort = 8081\nLogLevel = Debug\n".as_bytes())
+    ///     .unwrap();
+    /// assert_eq!(p.comment("HttpPort").as_deref(), Some("server settings\nport to listen on"));
+    /// assert_eq!(p.comment("LogLevel"), None);
+    ///
+    /// let mut p = builder().file_type_ini().preserve_comments().build();
+    /// p.load("[cache]\n; size in MB\nSize = 64\n".as_bytes()).unwrap();
+    /// assert_eq!(p.comment("cache.Size").as_deref(), Some("size in MB"));
+    /// ```
+    fn comment(&self, key: &str) -> Option<String>;
+    /// Sets a block of `#` lines that `store` writes before any key, one line per line of `text`,
+    /// for notes such as "Generated by deploy.sh, do not edit". An empty text removes the header.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 设置`store`时写在所有键之前的`#`注释块，`text`的每一行对应一行注释，例如"Generated by deploy.sh, do not edit"。空文本会删除文件头。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_header("Generated by deploy.sh\ndo not edit");
+    /// assert_eq!(
+    ///     p.store_to_string().unwrap(),
+    ///     "# Generated by deploy.sh\n# do not edit\nHttpPort = 8081\n"
+    /// );
+    /// ```
+    fn set_header(&mut self, text: &str);
+    /// Parses the property with the specified key as an integer like [`property_int`](Settings::property_int),
+    /// but says why it failed: [`SettingsError::Missing`] when the key is not set
+    /// and [`SettingsError::Invalid`] when the value is not a valid integer.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 与[`property_int`](Settings::property_int)一样将指定键的属性解析为整数，但会说明失败原因：键不存在时返回[`SettingsError::Missing`]，值不是合法整数时返回[`SettingsError::Invalid`]。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings, SettingsError};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("Host", "localhost");
+    /// assert_eq!(p.try_property_int("HttpPort").unwrap(), 8081);
+    ///
+    /// let err = p.try_property_int("Missing").unwrap_err();
+    /// assert!(matches!(err, SettingsError::Missing { ref key } if key == "Missing"));
+    /// assert_eq!(err.to_string(), "missing property \"Missing\"");
+    ///
+    /// let err = p.try_property_int("Host").unwrap_err();
+    /// assert!(matches!(err, SettingsError::Invalid { ref key, .. } if key == "Host"));
+    /// assert_eq!(err.to_string(), "invalid value for \"Host\": \"localhost\" is not an integer");
+    /// ```
+    fn try_property_int(&self, key: &str) -> Result<i64, SettingsError>;
+    /// Parses the property with the specified key as a duration such as `30s`, `500ms` or `2h`.
+    /// The number must be followed by one of the units `ms`, `s`, `m`, `h` or `d`;
+    /// returns `None` when the key is missing, the unit is absent or unknown, or the value overflows.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将指定键的属性解析为时长，例如`30s`、`500ms`或`2h`。数字后面必须跟单位`ms`、`s`、`m`、`h`或`d`之一；键不存在、缺少单位、单位未知或数值溢出时返回`None`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// use std::time::Duration;
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("ReadTimeout", "30s");
+    /// p.set_property("RetryDelay", "500ms");
+    /// p.set_property("SessionTtl", "2h");
+    /// p.set_property("Bad", "30x");
+    /// p.set_property("Bare", "30");
+    /// assert_eq!(p.property_duration("ReadTimeout"), Some(Duration::from_secs(30)));
+    /// assert_eq!(p.property_duration("RetryDelay"), Some(Duration::from_millis(500)));
+    /// assert_eq!(p.property_duration("SessionTtl"), Some(Duration::from_secs(2 * 60 * 60)));
+    /// assert_eq!(p.property_duration("Bad"), None);
+    /// assert_eq!(p.property_duration("Bare"), None);
+    /// ```
+    fn property_duration(&self, key: &str) -> Option<Duration>;
+    /// Parses the property with the specified key as a size in bytes such as `10MB` or `1GiB`.
+    /// `KB`, `MB` and `GB` are decimal (powers of 1000) and `KiB`, `MiB` and `GiB` are binary (powers of 1024);
+    /// a bare number or one ending in `B` is a count of bytes. Returns `None` for other units or on overflow.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将指定键的属性解析为字节数，例如`10MB`或`1GiB`。`KB`、`MB`和`GB`为十进制（1000的幂），`KiB`、`MiB`和`GiB`为二进制（1024的幂）；不带单位或以`B`结尾的数字表示字节数。其他单位或数值溢出时返回`None`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("MaxUpload", "10MB");
+    /// p.set_property("CacheSize", "1GiB");
+    /// p.set_property("BufferSize", "4096");
+    /// p.set_property("Bad", "10 bits");
+    /// assert_eq!(p.property_bytes("MaxUpload"), Some(10_000_000));
+    /// assert_eq!(p.property_bytes("CacheSize"), Some(1 << 30));
+    /// assert_eq!(p.property_bytes("BufferSize"), Some(4096));
+    /// assert_eq!(p.property_bytes("Bad"), None);
+    /// ```
+    fn property_bytes(&self, key: &str) -> Option<u64>;
+    /// Moves the property list into an [`Arc`] so it can be shared read-only between threads.
+    /// Every getter takes `&self` and only a shared lock, so the threads read concurrently.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将属性列表移入[`Arc`]，以便在线程之间只读共享。所有获取方法都只需要`&self`和共享锁，因此各线程可以并发读取。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// use std::thread;
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// let shared = p.into_shared();
+    /// let readers: Vec<_> = (0..4)
+    ///     .map(|_| {
+    ///         let shared = shared.clone();
+    ///         thread::spawn(move || (shared.property_int("HttpPort"), shared.property("LogLevel")))
+    ///     })
+    ///     .collect();
+    /// for reader in readers {
+    ///     assert_eq!(reader.join().unwrap(), (Some(8081), Some("Debug".to_owned())));
+    /// }
+    /// ```
+    fn into_shared(self) -> Arc<Properties>;
+    /// Reads a property list like [`load`](Settings::load), but skips the lines that do not parse and keeps going,
+    /// returning every problem with its 1-based line number once the whole input has been read.
+    /// A failure to read or decode the input still ends the load, and is reported on line 0.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 与[`load`](Settings::load)一样读取属性列表，但会跳过无法解析的行并继续读取，读完全部输入后返回所有问题及其从1开始的行号。读取或解码输入失败时仍会结束加载，并以行号0报告。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let text = "HttpPort = 8081\nBanner = \\u12\nLogLevel = Debug\nHttpPort = 8082\nName = \\uZZZZ\n";
+    /// let mut p = builder()
+    ///     .file_type_properties()
+    ///     .on_duplicate(gostd_settings::DuplicatePolicy::Error)
+    ///     .build();
+    /// let errors = p.load_lenient(text.as_bytes()).unwrap_err();
+    /// assert_eq!(
+    ///     errors,
+    ///     vec![
+    ///         (2, "malformed \\uXXXX encoding".to_owned()),
+    ///         (4, "duplicate key \"HttpPort\"".to_owned()),
+    ///         (5, "malformed \\uXXXX encoding".to_owned()),
+    ///     ]
+    /// );
+    /// assert_eq!(p.property_names(), vec!["HttpPort", "LogLevel"]);
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// ```
+    fn load_lenient(&mut self, r: impl Read) -> Result<(), Vec<(usize, String)>>;
+    /// Calls `f` with the underlying map while holding a read lock, for queries that should not copy the values.
+    /// With `case_insensitive` the map is keyed by the lowercased keys.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 在持有读锁期间以底层映射调用`f`，用于不想复制值的查询。启用`case_insensitive`时映射的键为小写形式。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("db.host", "10.11.1.5");
+    /// p.set_property("db.port", "27017");
+    /// p.set_property("HttpPort", "8081");
+    /// let db_keys = p.with_map(|map| map.keys().filter(|k| k.starts_with("db.")).count());
+    /// assert_eq!(db_keys, 2);
+    /// ```
+    fn with_map<R>(&self, f: impl FnOnce(&HashMap<String, String>) -> R) -> R;
+    /// Searches for the property with the specified key and returns it as a character
+    /// when the value, with surrounding whitespace trimmed, is exactly one character.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 用指定的键搜索属性，去掉首尾空白后的值恰好是一个字符时返回该字符。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_from_str("FieldSeparator = ;\nQuote =\nLineEnd = \\\\r\\\\n\n").unwrap();
+    /// assert_eq!(p.property_char("FieldSeparator"), Some(';'));
+    /// assert_eq!(p.property_char("Quote"), None);
+    /// assert_eq!(p.property_char("LineEnd"), None);
+    /// assert_eq!(p.property_char("Missing"), None);
+    /// ```
+    fn property_char(&self, key: &str) -> Option<char>;
+    /// Collects the values of the keys `base[0]`, `base[1]`, ... in index order, as written by tools
+    /// that store lists one element per key. Missing indexes are skipped, so gaps do not end the list.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 按索引顺序收集键`base[0]`、`base[1]`……的值，用于兼容每个元素单独存为一个键的工具。缺失的索引会被跳过，因此中间的空缺不会截断列表。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_from_str("servers[1] = 10.11.1.6\nservers[0] = 10.11.1.5\nservers[2] = 10.11.1.7\nservers = ignored\n")
+    ///     .unwrap();
+    /// assert_eq!(p.property_indexed("servers"), vec!["10.11.1.5", "10.11.1.6", "10.11.1.7"]);
+    /// assert!(p.property_indexed("clients").is_empty());
+    /// ```
+    fn property_indexed(&self, base: &str) -> Vec<String>;
+    /// Replaces the contents of the list with the property list in the file, where
+    /// [`load_from_file`](Settings::load_from_file) would add to the keys already set.
+    /// The file is read in full before anything is replaced, so on error the list is left unchanged.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 用文件中的属性列表替换当前内容，而[`load_from_file`](Settings::load_from_file)会在已有的键上追加。替换前会先完整读取文件，因此出错时列表保持不变。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let path = std::env::temp_dir().join("gostd_settings_reload_from_file.properties");
+    /// std::fs::write(&path, "HttpPort = 8082\n").unwrap();
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Debug");
+    /// p.load_from_file(&path).unwrap();
+    /// assert_eq!(p.property_names(), vec!["HttpPort", "LogLevel"]);
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Debug");
+    /// p.reload_from_file(&path).unwrap();
+    /// assert_eq!(p.property_names(), vec!["HttpPort"]);
+    ///
+    /// std::fs::write(&path, "Bad = \\u12\n").unwrap();
+    /// assert!(p.reload_from_file(&path).is_err());
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8082"));
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn reload_from_file(&mut self, file_path: impl AsRef<Path>) -> Result<(), SettingsError>;
+    /// Reads a compact map value such as `Accept:json,Auth:bearer`: the value is split into entries like
+    /// `property_slice` does, and each entry on its first `:` into a key and a value, both trimmed.
+    /// An entry without `:` maps to an empty value and empty entries are skipped. Returns `None` if the key is not set.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 读取紧凑的映射值，如`Accept:json,Auth:bearer`：先像`property_slice`一样把值拆分为条目，再按每个条目中第一个`:`拆分为键和值，并去掉两端空白。
+    /// 没有`:`的条目对应空值，空条目会被跳过。键不存在时返回`None`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load_from_str("headers = Accept:json, Auth:bearer\nflags = verbose,,level:2\n").unwrap();
+    /// let headers = p.property_map("headers").unwrap();
+    /// assert_eq!(headers.len(), 2);
+    /// assert_eq!(headers["Accept"], "json");
+    /// assert_eq!(headers["Auth"], "bearer");
+    /// let flags = p.property_map("flags").unwrap();
+    /// assert_eq!(flags.len(), 2);
+    /// assert_eq!(flags["verbose"], "");
+    /// assert_eq!(flags["level"], "2");
+    /// assert_eq!(p.property_map("missing"), None);
+    /// ```
+    fn property_map(&self, key: &str) -> Option<HashMap<String, String>>;
+    /// Writes the property list like `store`, but only the keys for which `keep` returns `true`, with their comments.
+    /// The list itself is not changed.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 与`store`一样写出属性列表，但只写出`keep`返回`true`的键及其注释。属性列表本身不会被修改。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("public.name", "demo");
+    /// p.set_property("public.port", "8081");
+    /// p.set_property("secret.token", "hunter2");
+    /// let mut out = Vec::new();
+    /// p.store_filtered(&mut out, |k| k.starts_with("public.")).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "public.name = demo\npublic.port = 8081\n"
+    /// );
+    /// assert_eq!(p.len(), 3);
+    ///
+    /// // sections without any kept key are left out
+    /// let mut p = builder().file_type_ini().build();
+    /// p.load_from_str("[public]\nname = demo\n[secret]\ntoken = hunter2\n").unwrap();
+    /// let mut out = Vec::new();
+    /// p.store_filtered(&mut out, |k| !k.starts_with("secret.")).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "[public]\nname = demo\n");
+    /// ```
+    fn store_filtered(
+        &self,
+        w: impl Write,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<(), SettingsError>;
+    /// Returns the [`Entry`] of the key for conditional updates like `HashMap::entry`.
+    /// The entry holds the write lock until it is consumed or dropped, so a read and the update that follows cannot be interleaved.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回键对应的[`Entry`]，用于类似`HashMap::entry`的条件更新。在被消费或丢弃之前，Entry一直持有写锁，因此读取与随后的更新不会被其他线程打断。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("LogLevel", "Debug");
+    /// p.entry("LogLevel").and_modify(|v| v.push_str(",Info")).or_insert("Warn");
+    /// p.entry("Hits").or_insert("0");
+    /// p.entry("Hits")
+    ///     .and_modify(|v| *v = (v.parse::<i64>().unwrap() + 1).to_string())
+    ///     .or_insert("0");
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug,Info"));
+    /// assert_eq!(p.property_int("Hits"), Some(1));
+    ///
+    /// fn numeric_ports(key: &str, value: &str) -> Result<(), String> {
+    ///     match key.ends_with("Port") && value.parse::<u16>().is_err() {
+    ///         true => Err(format!("{:?} is not a port number", value)),
+    ///         false => Ok(()),
+    ///     }
+    /// }
+    /// let mut p = builder().file_type_properties().validator(numeric_ports).build();
+    /// p.set_property("Port", "80");
+    /// p.entry("Port").and_modify(|v| v.push('x'));
+    /// assert_eq!(p.entry("AdminPort").or_insert("bad"), "bad");
+    /// assert_eq!(p.property("Port").as_deref(), Some("80"));
+    /// assert!(!p.contains_key("AdminPort"));
+    /// ```
+    fn entry(&mut self, key: &str) -> Entry<'_>;
+}
+
+pub fn builder() -> SettingsBuilder {
+    SettingsBuilder {
+        options: Options::default(),
+    }
+}
+
+/// Starts a builder for the given file format, like `builder()` followed by `file_type_properties`,
+/// `file_type_ini` or `file_type_env`. Useful when the format is only known at runtime.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// 以指定的文件格式创建构建器，相当于`builder()`之后调用`file_type_properties`、`file_type_ini`或`file_type_env`。适用于运行时才确定格式的场景。
+/// </details>
+///
+/// # Example
+/// ```
+/// use gostd_settings::{builder_with, Format, Settings};
+/// let format = if "app.env".ends_with(".env") { Format::Env } else { Format::Properties };
+/// let mut p = builder_with(format).build();
+/// p.load_from_str("HTTP_PORT=\"8081\"\n").unwrap();
+/// assert_eq!(p.property("HTTP_PORT").as_deref(), Some("8081"));
+///
+/// let mut p = builder_with(Format::Ini).preserve_order().build();
+/// p.set_property("server.port", "8081");
+/// assert_eq!(p.store_to_string().unwrap(), "[server]\nport = 8081\n");
+/// ```
+pub fn builder_with(format: Format) -> SettingsBuilder {
+    match format {
+        Format::Properties => builder().file_type_properties(),
+        Format::Ini => builder().file_type_ini(),
+        Format::Env => builder().file_type_env(),
+    }
+}
+
+/// A thread-safe property list, the concrete type behind [`Settings`].
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// 线程安全的属性列表，[`Settings`]的具体实现类型。
+/// </details>
+///
+/// # Example
+/// ```
+/// use gostd_settings::{builder, Properties, Settings};
+/// struct App {
+///     settings: Properties,
+/// }
+/// let mut app = App {
+///     settings: builder().file_type_properties().build_properties(),
+/// };
+/// app.settings.set_property("HttpPort", "8081");
+/// assert_eq!(app.settings.property("HttpPort").as_deref(), Some("8081"));
+/// ```
+///
+/// Reads take a shared lock, so threads looking up properties at the same time do not wait for each other;
+/// only the `&mut self` methods lock the list exclusively.
+///
+/// ```
+/// use gostd_settings::{builder, Settings};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let mut p = builder().file_type_properties().build_properties();
+/// for i in 0..100 {
+///     p.set_property(&format!("key{}", i), &i.to_string());
+/// }
+/// let p = Arc::new(p);
+/// let readers: Vec<_> = (0..8)
+///     .map(|_| {
+///         let p = Arc::clone(&p);
+///         thread::spawn(move || {
+///             (0..1000).all(|n| {
+///                 let i = n % 100;
+///                 p.property(&format!("key{}", i)) == Some(i.to_string())
+///             })
+///         })
+///     })
+///     .collect();
+/// assert!(readers.into_iter().all(|h| h.join().unwrap()));
+/// ```
+///
+/// A panic while the list is locked for writing, for example inside the closure passed to `Entry::and_modify`, does not make it unusable afterwards.
+///
+/// Cloning takes a snapshot that can be changed without affecting the original.
+///
+/// ```
+/// use gostd_settings::{builder, Settings};
+/// let mut p = builder().file_type_properties().build_properties();
+/// p.set_property("HttpPort", "8081");
+/// let mut draft = p.clone();
+/// draft.set_property("HttpPort", "8082");
+/// draft.set_property("LogLevel", "Debug");
+/// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+/// assert_eq!(p.len(), 1);
+/// assert_eq!(draft.property("HttpPort").as_deref(), Some("8082"));
+/// ```
+///
+/// Two lists are equal when they hold the same keys and values, whatever the order or comments in their source.
+///
+/// ```
+/// use gostd_settings::{builder, Settings};
+/// let mut a = builder().file_type_properties().build_properties();
+/// a.load("# defaults\nHttpPort = 8081\nLogLevel = Debug\n".as_bytes()).unwrap();
+/// let mut b = builder().file_type_properties().build_properties();
+/// b.load("LogLevel = Debug\nHttpPort = 8081\n".as_bytes()).unwrap();
+/// assert!(a == b);
+/// b.set_property("HttpPort", "8082");
+/// assert!(a != b);
+/// ```
+///
+/// The `Debug` output masks the values of keys that look like secrets, i.e. contain `password`, `secret`, `token` or `key` in any case.
+///
+/// ```
+/// use gostd_settings::{builder, Settings};
+/// let mut p = builder().file_type_properties().build_properties();
+/// p.set_property("HttpPort", "8081");
+/// p.set_property("Db.Password", "hunter2");
+/// let debug = format!("{:?}", p);
+/// assert_eq!(debug, r#"{"Db.Password": "****", "HttpPort": "8081"}"#);
+/// ```
+///
+/// A list with the default options can be collected from key-value pairs.
+///
+/// ```
+/// use gostd_settings::{Properties, Settings};
+/// let pairs = vec![
+///     ("HttpPort".to_string(), "8081".to_string()),
+///     ("LogLevel".to_string(), "Debug".to_string()),
+/// ];
+/// let p: Properties = pairs.into_iter().collect();
+/// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+/// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug"));
+/// assert_eq!(p.len(), 2);
+/// ```
+///
+/// With the `serde` feature enabled, a list serializes as a flat map of strings
+/// and deserializes from one with the default options.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use gostd_settings::{builder, Properties, Settings};
+/// let mut p = builder().file_type_properties().build_properties();
+/// p.set_property("HttpPort", "8081");
+/// p.set_property("LogLevel", "Debug");
+/// let json = serde_json::to_string(&p).unwrap();
+/// assert_eq!(json, r#"{"HttpPort":"8081","LogLevel":"Debug"}"#);
+/// let q: Properties = serde_json::from_str(&json).unwrap();
+/// assert_eq!(q, p);
+/// assert!(serde_json::from_str::<Properties>(r#"{"HttpPort":8081}"#).is_err());
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Properties {
+    object: RwLock<Table>,
+    options: Options,
+}
+
+impl fmt::Debug for Properties {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let object = self.read_table();
+        let mut map = f.debug_map();
+        for (k, v) in object.ordered(self.options.preserve_order) {
+            let lower = strings::ToLower(k);
+            if ["password", "secret", "token", "key"]
+                .iter()
+                .any(|s| lower.contains(s))
+            {
+                map.entry(k, &"****");
+            } else {
+                map.entry(k, v);
+            }
+        }
+        map.finish()
+    }
+}
+
+impl FromIterator<(String, String)> for Properties {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        let mut p = builder().build_properties();
+        p.set_properties(iter);
+        p
+    }
+}
+
+impl PartialEq for Properties {
+    fn eq(&self, other: &Self) -> bool {
+        // taking the same read lock twice can deadlock behind a waiting writer
+        std::ptr::eq(self, other) || self.read_table().map == other.read_table().map
+    }
+}
+
+impl Eq for Properties {}
+
+impl Clone for Properties {
+    fn clone(&self) -> Self {
+        Properties {
+            object: RwLock::new(self.read_table().clone()),
+            options: self.options.clone(),
+        }
+    }
+}
+
+/// Character encoding of the input read by `load`.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// `load`读取输入时使用的字符编码。
+/// </details>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, the default.
+    Utf8,
+    /// ISO-8859-1, the traditional encoding of Java `.properties` files.
+    Latin1,
+    /// UTF-16, little endian.
+    Utf16Le,
+    /// UTF-16, big endian.
+    Utf16Be,
+}
+
+impl Encoding {
+    fn decode(self, bytes: &[u8]) -> Result<String, Error> {
+        let unit: fn([u8; 2]) -> u16 = match self {
+            Encoding::Utf8 => {
+                return String::from_utf8(bytes.to_vec())
+                    .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+            }
+            Encoding::Latin1 => return Ok(bytes.iter().map(|&b| b as char).collect()),
+            Encoding::Utf16Le => u16::from_le_bytes,
+            Encoding::Utf16Be => u16::from_be_bytes,
+        };
+        let chunks = bytes.chunks_exact(2);
+        if !chunks.remainder().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "UTF-16 input has an odd number of bytes",
+            ));
+        }
+        let units: Vec<u16> = chunks.map(|b| unit([b[0], b[1]])).collect();
+        String::from_utf16(&units).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+    }
+}
+
+/// Line terminator written by `store`.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// `store`写入的换行符。
+/// </details>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, the default.
+    Lf,
+    /// `\r\n`, as expected by many Windows tools.
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Passes writes through, holding back a trailing line ending until more output follows,
+/// so the last line written is left unterminated.
+struct HoldNewline<'a, W: Write> {
+    inner: &'a mut W,
+    newline: &'static str,
+    held: bool,
+}
+
+impl<W: Write> Write for HoldNewline<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.held {
+            self.inner.write_all(self.newline.as_bytes())?;
+        }
+        self.held = buf.ends_with(self.newline.as_bytes());
+        let end = if self.held {
+            buf.len() - self.newline.len()
+        } else {
+            buf.len()
+        };
+        self.inner.write_all(&buf[..end])?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+}
+
+/// What `load` does when the input defines a key more than once.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// 输入中同一个键出现多次时`load`的处理方式。
+/// </details>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// The last definition wins, the default.
+    Overwrite,
+    /// The first definition wins and later ones are ignored.
+    KeepFirst,
+    /// `load` fails with a `SettingsError::Parse` naming the key and the line of the second definition.
+    /// With `case_insensitive`, the message names both spellings when they differ.
+    Error,
+}
+
+/// How `store` writes the case of keys, see `SettingsBuilder::key_case`.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// `store`写出键名时的大小写方式，参见`SettingsBuilder::key_case`。
+/// </details>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyCase {
+    /// Keys are written as they are set, the default.
+    AsIs,
+    /// Keys are written in upper case.
+    Upper,
+    /// Keys are written in lower case.
+    Lower,
+}
+
+/// Where the value of a key came from, as reported by `source_of`.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// `source_of`报告的键值来源。
+/// </details>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Read by `load` or one of the other load methods.
+    File,
+    /// Set by `set_property` or one of the other setters.
+    Runtime,
+    /// Inserted as the default by `property_or_insert`.
+    Default,
+}
+
+/// One difference between a property list and a file, as reported by `diff_file`.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// `diff_file`报告的属性列表与文件之间的一处差异。
+/// </details>
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The key is only in the property list.
+    Added { key: String, value: String },
+    /// The key is only in the file.
+    Removed { key: String, value: String },
+    /// The key has the value `old` in the file and `new` in the property list.
+    Changed {
+        key: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl Change {
+    /// Returns the key the change is about.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回差异所涉及的键。
+    /// </details>
+    pub fn key(&self) -> &str {
+        match self {
+            Change::Added { key, .. }
+            | Change::Removed { key, .. }
+            | Change::Changed { key, .. } => key,
+        }
+    }
+}
+
+/// A key of a property list together with the write lock, as returned by `entry`.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// 由`entry`返回的属性列表中的一个键，同时持有写锁。
+/// </details>
+pub struct Entry<'a> {
+    object: RwLockWriteGuard<'a, Table>,
+    key: String,
+    validator: Option<Validator>,
+}
+
+impl Entry<'_> {
+    /// Returns the key of the entry.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回该条目的键。
+    /// </details>
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+    /// Calls `f` with the value when the key is set and stores the result unless the builder's validator rejects it; does nothing otherwise.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 键存在时以其值调用`f`，并在构建器的校验函数接受时保存修改后的值，否则不做任何事。
+    /// </details>
+    pub fn and_modify(mut self, f: impl FnOnce(&mut String)) -> Self {
+        if let Some(mut value) = self.object.get(&self.key).cloned() {
+            f(&mut value);
+            if self.accepts(&value) {
+                self.object.insert(&self.key, &value, Source::Runtime);
+            }
+        }
+        self
+    }
+    /// Returns the value of the key, first inserting `default` when the key is absent, like `property_or_insert`.
+    /// A default rejected by the builder's validator is returned without being inserted.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 返回键对应的值；键不存在时先插入`default`，与`property_or_insert`相同。被构建器校验函数拒绝的默认值会被返回但不插入。
+    /// </details>
+    pub fn or_insert(mut self, default: &str) -> String {
+        if let Some(value) = self.object.get(&self.key) {
+            return value.to_owned();
+        }
+        if self.accepts(default) {
+            self.object.insert(&self.key, default, Source::Default);
+        }
+        default.to_owned()
+    }
+
+    /// Runs the builder's validator, like `set_property` does before setting a value.
+    fn accepts(&self, value: &str) -> bool {
+        match self.validator {
+            Some(validator) => validator(&self.key, value).is_ok(),
+            None => true,
+        }
+    }
+}
+
+/// File format read by `load` and written by `store`, see [`builder_with`].
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// `load`读取和`store`写入的文件格式，参见[`builder_with`]。
+/// </details>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Java-style `.properties` files, the default.
+    Properties,
+    /// INI files with `[section]` headers.
+    Ini,
+    /// `.env` files of `KEY=value` lines.
+    Env,
+}
+
+#[derive(Clone)]
+struct Options {
+    format: Format,
+    preserve_order: bool,
+    separator: String,
+    expand_env: bool,
+    case_insensitive: bool,
+    list_separator: char,
+    preserve_comments: bool,
+    comment_prefixes: Vec<String>,
+    strip_inline_comments: bool,
+    encoding: Encoding,
+    line_ending: LineEnding,
+    trailing_newline: bool,
+    on_duplicate: DuplicatePolicy,
+    quoted_values: bool,
+    validator: Option<Validator>,
+    block_values: bool,
+    max_line_bytes: Option<usize>,
+    namespace_delimiter: char,
+    key_case: KeyCase,
+}
+
+/// Checks a key and value before they are set, see `SettingsBuilder::validator`.
+type Validator = fn(&str, &str) -> Result<(), String>;
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            format: Format::Properties,
+            preserve_order: false,
+            separator: " = ".to_owned(),
+            expand_env: false,
+            case_insensitive: false,
+            list_separator: ',',
+            preserve_comments: false,
+            comment_prefixes: ["#", "!", "//", "/*"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            strip_inline_comments: false,
+            encoding: Encoding::Utf8,
+            line_ending: LineEnding::Lf,
+            trailing_newline: true,
+            on_duplicate: DuplicatePolicy::Overwrite,
+            quoted_values: false,
+            validator: None,
+            block_values: false,
+            max_line_bytes: None,
+            namespace_delimiter: '.',
+            key_case: KeyCase::AsIs,
+        }
+    }
+}
+
+/// Key-value pairs together with the order in which the keys were first inserted.
+/// With `ignore_case` the map is keyed by the lowercased key while `keys` keeps the original spelling.
+#[derive(Clone, Default)]
+struct Table {
+    map: HashMap<String, String>,
+    keys: Vec<String>,
+    ignore_case: bool,
+    /// Comment and blank lines preceding each key, by normalized key.
+    comments: HashMap<String, Vec<String>>,
+    /// Comment lines set with `set_header`, written before everything else.
+    header: Vec<String>,
+    /// Comment and blank lines after the last key.
+    trailer: Vec<String>,
+    /// Comment and blank lines preceding each INI `[section]` header, by normalized section name.
+    section_comments: HashMap<String, Vec<String>>,
+    /// Where each value came from, by normalized key.
+    sources: HashMap<String, Source>,
+}
+
+pub struct SettingsBuilder {
+    options: Options,
+}
+
+impl SettingsBuilder {
+    /// Reads and writes Java-style `.properties` files, which is the default.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 读写Java风格的`.properties`文件，这是默认格式。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder()
+    ///     .preserve_order()
+    ///     .separator("=")
+    ///     .file_type_properties()
+    ///     .build();
+    /// p.set_property("Zone", "east");
+    /// p.set_property("HttpPort", "8081");
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "Zone=east\nHttpPort=8081\n");
+    /// ```
+    pub fn file_type_properties(mut self) -> Self {
+        self.options.format = Format::Properties;
+        self
+    }
+    /// Reads and writes INI files: keys under a `[section]` header are namespaced as `section.key`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 读写INI文件：`[section]`段下的键以`section.key`的形式保存。
+    /// </details>
+    ///
+    /// Lines starting with `;` are comments as well. On `store`, keys without a `.` are written first, followed by one `[section]` block per prefix before the first `.`.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_ini().build();
+    /// let text = "Name = demo\n\n[database]\nHost = 10.11.1.5\nPort = 3306\n\n; cache settings\n[cache]\nSize = 64\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("Name").as_deref(), Some("demo"));
+    /// assert_eq!(p.property("database.Host").as_deref(), Some("10.11.1.5"));
+    /// assert_eq!(p.property("database.Port").as_deref(), Some("3306"));
+    /// assert_eq!(p.property("cache.Size").as_deref(), Some("64"));
+    ///
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "Name = demo\n\n[cache]\nSize = 64\n\n[database]\nHost = 10.11.1.5\nPort = 3306\n"
+    /// );
+    ///
+    /// // with preserve_comments, comments before a header stay in front of it
+    /// let mut p = builder().file_type_ini().preserve_comments().build();
+    /// let text = "Name = demo\n\n; cache settings\n[cache]\n; in MB\nSize = 64\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.store_to_string().unwrap(), text);
+    /// ```
+    pub fn file_type_ini(mut self) -> Self {
+        self.options.format = Format::Ini;
+        self
+    }
+    /// Reads and writes `.env` files: `KEY=value` lines with an optional `export` prefix and optionally quoted values.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 读写`.env`文件：`KEY=value`格式的行，可带`export`前缀，值可以加引号。
+    /// </details>
+    ///
+    /// Values in double quotes may use `\"`, `\\` and `\n`; values in single quotes are taken literally.
+    /// On `store`, lines are written as `KEY=value`, quoting values that contain whitespace, quotes, `#` or backslashes.
+    /// Keys may only use ASCII letters, digits, `_` and `.`, and must not start with a digit; `store` fails with
+    /// `SettingsError::Invalid` for any other key, since it could not be read back.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_env().build();
+    /// let text = "export DB_HOST=10.11.1.5\nGREETING=\"hello world\"\nPATTERN='a\\d+'\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("DB_HOST").as_deref(), Some("10.11.1.5"));
+    /// assert_eq!(p.property("GREETING").as_deref(), Some("hello world"));
+    /// assert_eq!(p.property("PATTERN").as_deref(), Some("a\\d+"));
+    ///
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "DB_HOST=10.11.1.5\nGREETING=\"hello world\"\nPATTERN=\"a\\\\d+\"\n"
+    /// );
+    /// ```
+    pub fn file_type_env(mut self) -> Self {
+        self.options.format = Format::Env;
+        self.options.separator = "=".to_owned();
+        self
+    }
+    /// Keeps keys in insertion order (the order they appear in a loaded file) when storing, instead of sorting them.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 保存时按键的插入顺序（即加载文件中出现的顺序）输出，而不是按字母排序。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().preserve_order().build();
+    /// let text = "Zone = east\nHttpPort = 8081\nLogLevel = Debug\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), text);
+    /// ```
+    pub fn preserve_order(mut self) -> Self {
+        self.options.preserve_order = true;
+        self
+    }
+    /// Sets the string written between each key and value by `store`, `" = "` by default.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 设置`store`写入时键和值之间的分隔符，默认为`" = "`。
+    /// </details>
+    ///
+    /// # Panics
+    /// Panics unless the separator is `=` or `:` with optional whitespace around it, since the output could not be loaded again otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().separator("=").build();
+    /// p.set_property("HttpPort", "8081");
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out.clone()).unwrap(), "HttpPort=8081\n");
+    ///
+    /// let mut q = builder().file_type_properties().build();
+    /// q.load(out.as_slice()).unwrap();
+    /// assert_eq!(q.property("HttpPort").as_deref(), Some("8081"));
+    /// ```
+    ///
+    /// A separator such as `":="` would be read back as part of the value, so it is rejected.
+    ///
+    /// ```should_panic
+    /// use gostd_settings::builder;
+    /// builder().file_type_properties().separator(" := ");
+    /// ```
+    pub fn separator(mut self, separator: &str) -> Self {
+        let trimmed = strings::TrimSpace(separator);
+        assert!(
+            trimmed == "=" || trimmed == ":",
+            "separator {:?} must be '=' or ':' with optional whitespace",
+            separator
+        );
+        self.options.separator = separator.to_owned();
+        self
+    }
+    /// Replaces `${VAR}` in loaded values with the environment variable `VAR`.
+    /// Unset variables are left as-is, and `$${VAR}` produces a literal `${VAR}`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 加载时把值中的`${VAR}`替换为环境变量`VAR`的值。未设置的变量保持原样，`$${VAR}`得到字面量`${VAR}`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// std::env::set_var("GOSTD_SETTINGS_DB_HOST", "10.11.1.5");
+    /// let mut p = builder().file_type_properties().expand_env().build();
+    /// let text = "DbHost = ${GOSTD_SETTINGS_DB_HOST}:3306\nUser = ${GOSTD_SETTINGS_UNSET}\nLiteral = $${GOSTD_SETTINGS_DB_HOST}\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("DbHost").as_deref(), Some("10.11.1.5:3306"));
+    /// assert_eq!(p.property("User").as_deref(), Some("${GOSTD_SETTINGS_UNSET}"));
+    /// assert_eq!(p.property("Literal").as_deref(), Some("${GOSTD_SETTINGS_DB_HOST}"));
+    ///
+    /// // `store` writes a literal `${` as `$${`, so it is not expanded on reload
+    /// let text = p.store_to_string().unwrap();
+    /// assert!(text.contains("Literal = $${GOSTD_SETTINGS_DB_HOST}\n"));
+    /// let mut q = builder().file_type_properties().expand_env().build();
+    /// q.load_from_str(&text).unwrap();
+    /// assert_eq!(q.property("Literal").as_deref(), Some("${GOSTD_SETTINGS_DB_HOST}"));
+    /// ```
+    pub fn expand_env(mut self) -> Self {
+        self.options.expand_env = true;
+        self
+    }
+    /// Matches keys case-insensitively, so `property("HTTPPORT")` finds a key stored as `HttpPort`.
+    /// Keys keep the spelling they were first inserted with in `property_names`, `iter` and `store`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 键不区分大小写，`property("HTTPPORT")`可以找到以`HttpPort`保存的键。`property_names`、`iter`和`store`中保留键首次插入时的写法。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().case_insensitive().build();
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(p.property("HTTPPORT").as_deref(), Some("8081"));
+    /// p.set_property("httpport", "8082");
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8082"));
+    /// assert_eq!(p.property_names(), vec!["HttpPort"]);
+    /// assert_eq!(p.remove_property("HTTPPORT").as_deref(), Some("8082"));
+    /// assert!(p.is_empty());
+    /// ```
+    pub fn case_insensitive(mut self) -> Self {
+        self.options.case_insensitive = true;
+        self
+    }
+    /// Sets the separator used by `property_slice` and `set_property_slice`, `,` by default.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 设置`property_slice`和`set_property_slice`使用的多值分隔符，默认为`,`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().list_separator(';').build();
+    /// p.set_property_slice("LogLevel", vec!["Debug".to_string(), "Info".to_string()]);
+    /// assert_eq!(p.property("LogLevel").as_deref(), Some("Debug;Info"));
+    /// assert_eq!(p.property_slice("LogLevel"), Some(vec!["Debug".to_string(), "Info".to_string()]));
+    ///
+    /// let mut p = builder().file_type_properties().list_separator('|').build();
+    /// p.load("Columns = id,name|age,city\n".as_bytes()).unwrap();
+    /// assert_eq!(
+    ///     p.property_slice("Columns"),
+    ///     Some(vec!["id,name".to_string(), "age,city".to_string()])
+    /// );
+    /// ```
+    pub fn list_separator(mut self, separator: char) -> Self {
+        self.options.list_separator = separator;
+        self
+    }
+    /// Keeps comment and blank lines from loaded files and writes them back on `store`.
+    /// Each block of comment lines stays in front of the key that follows it; lines after the last key stay at the end.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 保留加载文件中的注释和空行，并在`store`时写回。每段注释保持在其后面的键之前，最后一个键之后的行保留在末尾。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder()
+    ///     .file_type_properties()
+    ///     .preserve_order()
+    ///     .preserve_comments()
+    ///     .build();
+    /// let text = "# server settings\nHttpPort = 8081\n\n# logging\nLogLevel = Debug\n# end of file\n";
+    /// p.load(text.as_bytes()).unwrap();
+    /// p.set_property("HttpPort", "8082");
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "# server settings\nHttpPort = 8082\n\n# logging\nLogLevel = Debug\n# end of file\n"
+    /// );
+    /// ```
+    pub fn preserve_comments(mut self) -> Self {
+        self.options.preserve_comments = true;
+        self
+    }
+    /// Sets the prefixes that mark a line as a comment, `#`, `!`, `//` and `/*` by default.
+    /// Only the start of a line is checked, so a value such as `http://example.com` is never cut.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 设置注释行的前缀，默认为`#`、`!`、`//`和`/*`。只检查行首，所以`http://example.com`这样的值不会被截断。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let text = "! java style comment\n# shell style comment\nCdn = //cdn.example.com\n//Legacy = 1\n";
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property_names(), vec!["Cdn"]);
+    /// assert_eq!(p.property("Cdn").as_deref(), Some("//cdn.example.com"));
+    ///
+    /// let mut p = builder().file_type_properties().comment_prefixes(&["#", "!"]).build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("//Legacy").as_deref(), Some("1"));
+    /// ```
+    pub fn comment_prefixes(mut self, prefixes: &[&str]) -> Self {
+        self.options.comment_prefixes = prefixes.iter().map(|s| s.to_string()).collect();
+        self
+    }
+    /// Cuts loaded values at the first unescaped `#` or `;`, so `key = value # comment` reads as `value`.
+    /// Write `\#` or `\;` to keep the character in the value.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 加载时在第一个未转义的`#`或`;`处截断值，`key = value # comment`读取为`value`。用`\#`或`\;`在值中保留该字符。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let text = "HttpPort = 8081 # default port\nColor = \\#ff0000 ; red\n";
+    /// let mut p = builder().file_type_properties().strip_inline_comments().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    /// assert_eq!(p.property("Color").as_deref(), Some("#ff0000"));
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081 # default port"));
+    ///
+    /// // `store` escapes the characters, so the values are read back unchanged
+    /// let mut p = builder().file_type_properties().strip_inline_comments().build();
+    /// p.set_property("Color", "#ff0000");
+    /// p.set_property("Path", "a;b");
+    /// let text = p.store_to_string().unwrap();
+    /// assert_eq!(text, "Color = \\#ff0000\nPath = a\\;b\n");
+    /// let mut q = builder().file_type_properties().strip_inline_comments().build();
+    /// q.load_from_str(&text).unwrap();
+    /// assert_eq!(q.property("Color").as_deref(), Some("#ff0000"));
+    /// assert_eq!(q.property("Path").as_deref(), Some("a;b"));
+    ///
+    /// let mut p = builder().file_type_env().strip_inline_comments().build();
+    /// p.set_property("PATH_LIST", "a;b");
+    /// let text = p.store_to_string().unwrap();
+    /// assert_eq!(text, "PATH_LIST=\"a;b\"\n");
+    /// let mut q = builder().file_type_env().strip_inline_comments().build();
+    /// q.load_from_str(&text).unwrap();
+    /// assert_eq!(q.property("PATH_LIST").as_deref(), Some("a;b"));
+    /// ```
+    pub fn strip_inline_comments(mut self) -> Self {
+        self.options.strip_inline_comments = true;
+        self
+    }
+    /// Decodes the input of `load` with the given encoding instead of UTF-8. `store` always writes UTF-8.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// `load`使用指定编码而不是UTF-8解码输入。`store`始终写入UTF-8。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Encoding, Settings};
+    /// // "Name = René\nCity = Zürich\n" in ISO-8859-1
+    /// let latin1 = b"Name = Ren\xe9\nCity = Z\xfcrich\n";
+    /// let mut p = builder().file_type_properties().encoding(Encoding::Latin1).build();
+    /// p.load(&latin1[..]).unwrap();
+    /// assert_eq!(p.property("Name").as_deref(), Some("René"));
+    /// assert_eq!(p.property("City").as_deref(), Some("Zürich"));
+    ///
+    /// let utf16: Vec<u8> = "\u{feff}Name = René\n"
+    ///     .encode_utf16()
+    ///     .flat_map(|u| u.to_le_bytes())
+    ///     .collect();
+    /// let mut p = builder().file_type_properties().encoding(Encoding::Utf16Le).build();
+    /// p.load(utf16.as_slice()).unwrap();
+    /// assert_eq!(p.property("Name").as_deref(), Some("René"));
+    ///
+    /// let mut p = builder().file_type_properties().encoding(Encoding::Utf16Be).build();
+    /// assert!(p.load(&[0x00, 0x41, 0x00][..]).is_err());
+    /// ```
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.options.encoding = encoding;
+        self
+    }
+    /// Ends every line written by `store` with the given terminator instead of `\n`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// `store`写入的每一行以指定的换行符结尾，而不是`\n`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, LineEnding, Settings};
+    /// let mut p = builder()
+    ///     .file_type_ini()
+    ///     .line_ending(LineEnding::CrLf)
+    ///     .build();
+    /// p.set_property("name", "demo");
+    /// p.set_property("server.port", "8081");
+    /// assert_eq!(
+    ///     p.store_to_string().unwrap(),
+    ///     "name = demo\r\n\r\n[server]\r\nport = 8081\r\n"
+    /// );
+    ///
+    /// let mut q = builder().file_type_ini().build();
+    /// q.load_from_str(&p.store_to_string().unwrap()).unwrap();
+    /// assert_eq!(q.iter(), p.iter());
+    /// ```
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.options.line_ending = line_ending;
+        self
+    }
+    /// Controls whether `store` ends the last line with a line terminator, which it does by default.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 控制`store`是否在最后一行末尾写入换行符，默认写入。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// assert_eq!(p.store_to_string().unwrap().as_bytes().last(), Some(&b'\n'));
+    ///
+    /// let mut p = builder().file_type_properties().trailing_newline(false).build();
+    /// p.set_property("HttpPort", "8081");
+    /// p.set_property("LogLevel", "Debug");
+    /// let mut out = Vec::new();
+    /// p.store(&mut out).unwrap();
+    /// assert_eq!(out.last(), Some(&b'g'));
+    /// assert_eq!(String::from_utf8(out).unwrap(), "HttpPort = 8081\nLogLevel = Debug");
+    /// ```
+    pub fn trailing_newline(mut self, enabled: bool) -> Self {
+        self.options.trailing_newline = enabled;
+        self
+    }
+    /// Chooses what `load` does with a key defined twice in the same input. Keys set before the call are not duplicates.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 选择`load`如何处理同一输入中定义了两次的键。调用之前已设置的键不算重复。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, DuplicatePolicy, Settings, SettingsError};
+    /// let text = "HttpPort = 8081\nLogLevel = Debug\nHttpPort = 8082\n";
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8082"));
+    ///
+    /// let mut p = builder()
+    ///     .file_type_properties()
+    ///     .on_duplicate(DuplicatePolicy::KeepFirst)
+    ///     .build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    ///
+    /// let mut p = builder()
+    ///     .file_type_properties()
+    ///     .on_duplicate(DuplicatePolicy::Error)
+    ///     .build();
+    /// match p.load(text.as_bytes()) {
+    ///     Err(SettingsError::Parse { line, msg }) => {
+    ///         assert_eq!(line, 3);
+    ///         assert_eq!(msg, "duplicate key \"HttpPort\"");
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// // a second load may redefine keys from the first one
+    /// p.load("HttpPort = 8083\n".as_bytes()).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8083"));
+    ///
+    /// // with case_insensitive, differently cased spellings of a key are duplicates too
+    /// let mut p = builder()
+    ///     .file_type_properties()
+    ///     .case_insensitive()
+    ///     .on_duplicate(DuplicatePolicy::Error)
+    ///     .build();
+    /// let err = p.load("Host = a.example.com\nPort = 80\nhost = b.example.com\n".as_bytes()).unwrap_err();
+    /// assert_eq!(err.to_string(), "line 3: key \"host\" collides with \"Host\"");
+    /// ```
+    pub fn on_duplicate(mut self, policy: DuplicatePolicy) -> Self {
+        self.options.on_duplicate = policy;
+        self
+    }
+    /// Reads a value written as `"..."` as the literal text between the quotes, keeping its spaces, separators and commas.
+    /// Inside the quotes `\"` stands for a quote and the usual escapes apply.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将写成`"..."`的值读取为引号之间的原文，保留其中的空格、分隔符和逗号。引号内`\"`表示引号，其他转义照常处理。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let text = r#"Equation = "a = b, c"
+    /// Padded = "  keep me  "
+    /// Said = "she said \"hi\""
+    /// Plain = a, b
+    /// "#;
+    /// let mut p = builder().file_type_properties().quoted_values().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("Equation").as_deref(), Some("a = b, c"));
+    /// assert_eq!(p.property("Padded").as_deref(), Some("  keep me  "));
+    /// assert_eq!(p.property("Said").as_deref(), Some("she said \"hi\""));
+    /// assert_eq!(p.property("Plain").as_deref(), Some("a, b"));
+    ///
+    /// // a value that really starts with a quote survives a round trip
+    /// p.set_property("Title", "\"quoted\"");
+    /// let mut q = builder().file_type_properties().quoted_values().build();
+    /// q.load_from_str(&p.store_to_string().unwrap()).unwrap();
+    /// assert_eq!(q.property("Title").as_deref(), Some("\"quoted\""));
+    ///
+    /// let mut p = builder().file_type_properties().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(p.property("Equation").as_deref(), Some("\"a = b, c\""));
+    /// ```
+    pub fn quoted_values(mut self) -> Self {
+        self.options.quoted_values = true;
+        self
+    }
+    /// Checks every key and value before it is set, including those read by `load` and `load_json`.
+    /// `set_property`, `set_properties` and `merge` skip rejected values, `property_or_insert` returns a rejected default without inserting it,
+    /// `try_set_property` and `load_json` return `SettingsError::Invalid`, and `load` fails with a `SettingsError::Parse` for the offending line.
+    /// Renaming keys does not run the validator again.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 在设置每个键值（包括`load`和`load_json`读取的）之前进行检查。`set_property`、`set_properties`和`merge`会跳过被拒绝的值，
+    /// `property_or_insert`返回被拒绝的默认值但不插入，`try_set_property`和`load_json`返回`SettingsError::Invalid`，`load`则对出错的行返回`SettingsError::Parse`。
+    /// 重命名键时不会再次调用校验函数。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings, SettingsError};
+    /// fn numeric_ports(key: &str, value: &str) -> Result<(), String> {
+    ///     if key.ends_with("Port") && value.parse::<u16>().is_err() {
+    ///         return Err(format!("{:?} is not a port number", value));
+    ///     }
+    ///     Ok(())
+    /// }
+    /// let mut p = builder().file_type_properties().validator(numeric_ports).build();
+    /// match p.try_set_property("HttpPort", "eighty") {
+    ///     Err(SettingsError::Invalid { key, msg }) => {
+    ///         assert_eq!(key, "HttpPort");
+    ///         assert_eq!(msg, "\"eighty\" is not a port number");
+    ///     }
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// p.set_property("HttpPort", "eighty");
+    /// assert!(!p.contains_key("HttpPort"));
+    /// p.set_property("HttpPort", "8081");
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    ///
+    /// let err = p.load("Name = demo\nAdminPort = x\n".as_bytes()).unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "line 2: invalid value for \"AdminPort\": \"x\" is not a port number"
+    /// );
+    ///
+    /// assert_eq!(p.property_or_insert("AdminPort", "bad"), "bad");
+    /// assert!(!p.contains_key("AdminPort"));
+    /// let other: gostd_settings::Properties =
+    ///     vec![("AdminPort".to_string(), "bad".to_string())].into_iter().collect();
+    /// p.merge(&other);
+    /// assert!(!p.contains_key("AdminPort"));
+    /// let err = p.load_json(r#"{"Name": "x", "AdminPort": "bad"}"#.as_bytes()).unwrap_err();
+    /// assert!(matches!(err, SettingsError::Invalid { .. }));
+    /// assert_eq!(p.property("Name").as_deref(), Some("demo"));
+    /// ```
+    pub fn validator(mut self, validator: fn(&str, &str) -> Result<(), String>) -> Self {
+        self.options.validator = Some(validator);
+        self
+    }
+    /// Reads `key = <<END` followed by lines up to one that only holds `END` as a multi-line value.
+    /// The lines in between are kept verbatim, without unescaping, and joined with `\n`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 将`key = <<END`及其后直到仅包含`END`的行之间的内容读取为多行值。中间的行原样保留，不做反转义，并用`\n`连接。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let text = "Name = demo
+    /// Cert = <<PEM
+    /// -----BEGIN CERTIFICATE-----
+    /// MIIBszCCAVmgAwIBAgIU\\
+    /// ! not a comment
+    /// -----END CERTIFICATE-----
+    /// PEM
+    /// HttpPort = 8081
+    /// ";
+    /// let mut p = builder().file_type_properties().block_values().build();
+    /// p.load(text.as_bytes()).unwrap();
+    /// assert_eq!(
+    ///     p.property("Cert").as_deref(),
+    ///     Some("-----BEGIN CERTIFICATE-----\nMIIBszCCAVmgAwIBAgIU\\\n! not a comment\n-----END CERTIFICATE-----")
+    /// );
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    ///
+    /// let err = p.load("Sql = <<SQL\nselect 1;\n".as_bytes()).unwrap_err();
+    /// assert_eq!(err.to_string(), "line 1: block is missing its closing \"SQL\"");
+    ///
+    /// // `store` escapes a value that would start a block, so it is read back unchanged
+    /// let mut p = builder().file_type_properties().block_values().build();
+    /// p.set_property("Marker", "<<EOF");
+    /// let text = p.store_to_string().unwrap();
+    /// assert_eq!(text, "Marker = \\<<EOF\n");
+    /// let mut q = builder().file_type_properties().block_values().build();
+    /// q.load_from_str(&text).unwrap();
+    /// assert_eq!(q.property("Marker").as_deref(), Some("<<EOF"));
+    /// ```
+    pub fn block_values(mut self) -> Self {
+        self.options.block_values = true;
+        self
+    }
+    /// Makes `load` fail when a line, with its continuation lines joined, is longer than `limit` bytes.
+    /// With the default UTF-8 encoding reading stops at the limit, so a huge line in a damaged file is never held in memory;
+    /// other encodings decode the whole input before the lines are checked. Lines are unlimited by default.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 当一行（连同其续行）超过`limit`字节时使`load`失败。使用默认的UTF-8编码时，读取在达到上限时即停止，因此损坏文件中的超长行不会被整个读入内存；其他编码会先解码全部输入再检查各行。默认不限制行长度。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().max_line_bytes(16).build();
+    /// p.load("HttpPort = 8081\r\n".as_bytes()).unwrap();
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+    ///
+    /// let huge = format!("HttpPort = 8081\nBanner = {}\n", "x".repeat(1 << 20));
+    /// let err = p.load(huge.as_bytes()).unwrap_err();
+    /// assert_eq!(err.to_string(), "line 2: line is longer than 16 bytes");
+    ///
+    /// let err = p.load("Level = Debug,\\\n    Info\n".as_bytes()).unwrap_err();
+    /// assert_eq!(err.to_string(), "line 1: line is longer than 16 bytes");
+    ///
+    /// // a byte order mark does not count towards the limit, and does not let a long line through
+    /// let mut p = builder().file_type_properties().max_line_bytes(16).build();
+    /// p.load(&b"\xef\xbb\xbfHttpPort = 80812\n"[..]).unwrap();
+    /// assert_eq!(p.property_names(), vec!["HttpPort"]);
+    /// assert_eq!(p.property("HttpPort").as_deref(), Some("80812"));
+    /// let err = p.load(&b"\xef\xbb\xbfHttpPort = 808123\n"[..]).unwrap_err();
+    /// assert_eq!(err.to_string(), "line 1: line is longer than 16 bytes");
+    ///
+    /// let mut p = builder().file_type_properties().max_line_bytes(16).build();
+    /// let errors = p.load_lenient(huge.as_bytes()).unwrap_err();
+    /// assert_eq!(errors, vec![(2, "line is longer than 16 bytes".to_string())]);
+    /// assert_eq!(p.property_names(), vec!["HttpPort"]);
+    ///
+    /// let mut p = builder().max_line_bytes(usize::MAX).build();
+    /// p.load("a = 1\n".as_bytes()).unwrap();
+    /// assert_eq!(p.property("a").as_deref(), Some("1"));
+    /// ```
+    pub fn max_line_bytes(mut self, limit: usize) -> Self {
+        self.options.max_line_bytes = Some(limit);
+        self
+    }
+    /// Sets the delimiter between a namespace and the rest of a key, `.` by default.
+    /// It is the delimiter `sub` strips and the one that joins an INI `[section]` to its keys on load and splits them on `store`.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 设置命名空间与键名其余部分之间的分隔符，默认为`.`。`sub`按它去掉前缀，INI文件加载时用它连接`[section]`和键名，`store`时用它拆分。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// let mut p = builder().file_type_properties().namespace_delimiter('/').build();
+    /// p.set_property("db/host", "10.11.1.5");
+    /// p.set_property("db/port", "27017");
+    /// p.set_property("db.name", "demo");
+    /// assert_eq!(
+    ///     p.properties_with_prefix("db/"),
+    ///     vec![
+    ///         ("db/host".to_string(), "10.11.1.5".to_string()),
+    ///         ("db/port".to_string(), "27017".to_string()),
+    ///     ]
+    /// );
+    /// assert_eq!(p.sub("db").property_names(), vec!["host", "port"]);
+    ///
+    /// let mut p = builder().file_type_ini().namespace_delimiter(':').build();
+    /// p.load("[db]\nhost = 10.11.1.5\n".as_bytes()).unwrap();
+    /// assert_eq!(p.property("db:host").as_deref(), Some("10.11.1.5"));
+    /// assert_eq!(p.store_to_string().unwrap(), "[db]\nhost = 10.11.1.5\n");
+    /// ```
+    pub fn namespace_delimiter(mut self, delimiter: char) -> Self {
+        self.options.namespace_delimiter = delimiter;
+        self
+    }
+    /// Sets the case `store` writes keys in, and INI section names with them; the keys in the list are not changed.
+    /// `KeyCase::Upper` suits `.env` files, whose keys are conventionally upper case. Keys are written as set by default.
+    /// Storing fails with `SettingsError::Invalid` when two keys, such as `a` and `A`, would be written the same.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 设置`store`写出键名（以及INI段名）时的大小写，属性列表中的键不受影响。`KeyCase::Upper`适用于键名习惯大写的`.env`文件。默认按设置时的原样写出。
+    /// 当两个键（如`a`和`A`）会被写成相同的键时，保存失败并返回`SettingsError::Invalid`。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, KeyCase, Settings};
+    /// let mut p = builder().file_type_env().key_case(KeyCase::Upper).build();
+    /// p.set_property("http_port", "8081");
+    /// p.set_property("log_level", "Debug");
+    /// assert_eq!(p.store_to_string().unwrap(), "HTTP_PORT=8081\nLOG_LEVEL=Debug\n");
+    /// assert_eq!(p.property("http_port").as_deref(), Some("8081"));
+    /// p.set_property("HTTP_PORT", "8082");
+    /// let err = p.store_to_string().unwrap_err();
+    /// assert_eq!(err.to_string(), "invalid value for \"http_port\": is written the same as \"HTTP_PORT\"");
+    ///
+    /// let mut p = builder().file_type_ini().key_case(KeyCase::Lower).build();
+    /// p.set_property("Server.Port", "8081");
+    /// assert_eq!(p.store_to_string().unwrap(), "[server]\nport = 8081\n");
+    /// ```
+    pub fn key_case(mut self, key_case: KeyCase) -> Self {
+        self.options.key_case = key_case;
+        self
+    }
+    pub fn build(self) -> impl Settings {
+        self.build_properties()
+    }
+    /// Builds the concrete [`Properties`] type, for example to store it in a named struct field.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 构建具体的[`Properties`]类型，例如用于保存在结构体字段中。
+    /// </details>
+    pub fn build_properties(self) -> Properties {
+        Properties {
+            object: RwLock::new(Table {
+                ignore_case: self.options.case_insensitive,
+                ..Table::default()
+            }),
+            options: self.options,
+        }
+    }
+}
+
+/// A value taken out of a [`Table`] together with its comments and source.
+struct Moved {
+    value: String,
+    comments: Option<Vec<String>>,
+    source: Source,
+}
+
+impl Table {
+    fn normalize(&self, key: &str) -> String {
+        if self.ignore_case {
+            strings::ToLower(key)
+        } else {
+            key.to_owned()
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&String> {
+        self.map.get(&self.normalize(key))
+    }
+
+    fn insert(&mut self, key: &str, value: &str, source: Source) {
+        let normalized = self.normalize(key);
+        self.sources.insert(normalized.clone(), source);
+        if self.map.insert(normalized, value.to_owned()).is_none() {
+            self.keys.push(key.to_owned());
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<String> {
+        let normalized = self.normalize(key);
+        let value = self.map.remove(&normalized);
+        if value.is_some() {
+            self.comments.remove(&normalized);
+            self.sources.remove(&normalized);
+            let ignore_case = self.ignore_case;
+            self.keys
+                .retain(|k| k != key && !(ignore_case && strings::ToLower(k) == normalized));
+        }
+        value
+    }
+
+    /// Removes `key`, returning its entry so it can be inserted again under another name.
+    fn take(&mut self, key: &str) -> Option<Moved> {
+        let normalized = self.normalize(key);
+        let comments = self.comments.remove(&normalized);
+        let source = self.sources.get(&normalized).copied();
+        let value = self.remove(key)?;
+        Some(Moved {
+            value,
+            comments,
+            source: source.unwrap_or(Source::Runtime),
+        })
+    }
+
+    fn put(&mut self, key: &str, entry: Moved) {
+        self.insert(key, &entry.value, entry.source);
+        if let Some(comments) = entry.comments {
+            self.comments.insert(self.normalize(key), comments);
+        }
+    }
+
+    /// Moves the value, comments and source of `old` to `new`.
+    fn rename(&mut self, old: &str, new: &str) -> bool {
+        match self.take(old) {
+            Some(entry) => {
+                self.put(new, entry);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Renames every key under `old_prefix`, removing them all before inserting any,
+    /// so a renamed key never overwrites another key that is still waiting to be renamed.
+    fn rename_prefix(&mut self, old_prefix: &str, new_prefix: &str) -> usize {
+        let prefix = self.normalize(old_prefix);
+        let skip = old_prefix.chars().count();
+        let matching: Vec<String> = self
+            .keys
+            .iter()
+            .filter(|k| strings::HasPrefix(self.normalize(k), &prefix))
+            .cloned()
+            .collect();
+        let moved: Vec<(String, Moved)> = matching
+            .iter()
+            .filter_map(|key| {
+                let renamed: String = key.chars().skip(skip).collect();
+                Some((format!("{}{}", new_prefix, renamed), self.take(key)?))
+            })
+            .collect();
+        let count = moved.len();
+        for (key, entry) in moved {
+            self.put(&key, entry);
+        }
+        count
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.map.contains_key(&self.normalize(key))
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.keys.clear();
+        self.comments.clear();
+        self.header.clear();
+        self.trailer.clear();
+        self.section_comments.clear();
+        self.sources.clear();
+    }
+
+    fn comments(&self, key: &str) -> &[String] {
+        self.comments
+            .get(&self.normalize(key))
+            .map_or(&[], |lines| lines.as_slice())
+    }
+
+    /// Pairs in insertion order when `preserve_order` is set, otherwise sorted by key.
+    fn ordered(&self, preserve_order: bool) -> Vec<(&String, &String)> {
+        let mut keys: Vec<&String> = self.keys.iter().collect();
+        if !preserve_order {
+            keys.sort();
+        }
+        keys.into_iter()
+            .map(|k| (k, &self.map[&self.normalize(k)]))
+            .collect()
+    }
+}
+
+impl Properties {
+    /// Locks the table for reading, recovering it when another thread panicked while holding the lock.
+    /// Every write to the table is a single insert or remove, so a poisoned table is still consistent.
+    fn read_table(&self) -> RwLockReadGuard<'_, Table> {
+        self.object.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the table for writing, recovering it like `read_table`.
+    fn write_table(&self) -> RwLockWriteGuard<'_, Table> {
+        self.object.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn line(&self, key: &str, value: &str, w: &mut impl Write) -> Result<(), Error> {
+        let newline = self.options.line_ending.as_str();
+        if self.options.format == Format::Env {
+            write!(
+                w,
+                "{}{}{}{}",
+                self.cased_key(key),
+                self.options.separator,
+                self.quote(value),
+                newline
+            )
+        } else {
+            write!(
+                w,
+                "{}{}{}{}",
+                self.escape_key(&self.cased_key(key)),
+                self.options.separator,
+                self.escape_value(value),
+                newline
+            )
+        }
+    }
+
+    /// Splits a value such as `30s` or `10 MB` into its number and unit.
+    fn split_unit(value: &str) -> Option<(u64, &str)> {
+        let value = strings::TrimSpace(value);
+        let digits = value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len());
+        let n = value[..digits].parse().ok()?;
+        Some((n, strings::TrimSpace(&value[digits..])))
+    }
+
+    /// Joins list elements with the separator, escaping backslashes and separators inside the elements.
+    fn join_list(values: &[String], sep: &str) -> String {
+        let escaped: Vec<String> = values
+            .iter()
+            .map(|v| {
+                let v = strings::ReplaceAll(v, "\\", "\\\\");
+                strings::ReplaceAll(v, sep, format!("\\{}", sep))
+            })
+            .collect();
+        strings::Join(escaped, sep)
+    }
+
+    /// Splits a value at every unescaped separator, trimming the elements; the inverse of `join_list`.
+    fn split_list(value: &str, sep: &str) -> Vec<String> {
+        if strings::TrimSpace(value).is_empty() {
+            return vec![];
+        }
+        let mut items = vec![];
+        let mut item = String::new();
+        let mut rest = value;
+        while let Some(c) = rest.chars().next() {
+            if c == '\\' {
+                let escaped = &rest[1..];
+                if !sep.is_empty() && strings::HasPrefix(escaped, sep) {
+                    item.push_str(sep);
+                    rest = &escaped[sep.len()..];
+                    continue;
+                }
+                if strings::HasPrefix(escaped, "\\") {
+                    item.push('\\');
+                    rest = &escaped[1..];
+                    continue;
+                }
+            }
+            if !sep.is_empty() && strings::HasPrefix(rest, sep) {
+                items.push(strings::TrimSpace(&item).to_owned());
+                item.clear();
+                rest = &rest[sep.len()..];
+                continue;
+            }
+            item.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        items.push(strings::TrimSpace(&item).to_owned());
+        items
+    }
+
+    /// Validates and sets a property, recording where it came from.
+    fn set_checked(&mut self, key: &str, value: &str, source: Source) -> Result<(), SettingsError> {
+        self.validate(key, value)?;
+        self.write_table().insert(key, value, source);
+        Ok(())
+    }
+
+    /// Runs the builder's validator, if any, on a key and value about to be set.
+    fn validate(&self, key: &str, value: &str) -> Result<(), SettingsError> {
+        match self.options.validator {
+            Some(validator) => validator(key, value).map_err(|msg| SettingsError::Invalid {
+                key: key.to_owned(),
+                msg,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Loads a file with the same options as this list, or returns an empty list when the file does not exist.
+    /// With `keep_layout`, the file's comments and key order are kept whatever this list's options are.
+    fn load_existing(
+        &self,
+        file_path: impl AsRef<Path>,
+        keep_layout: bool,
+    ) -> Result<Properties, SettingsError> {
+        let mut options = self.options.clone();
+        if keep_layout {
+            options.preserve_comments = true;
+            options.preserve_order = true;
+        }
+        let mut file = SettingsBuilder { options }.build_properties();
+        match file.load_from_file(file_path) {
+            Err(SettingsError::Io(err)) if err.kind() == ErrorKind::NotFound => Ok(file),
+            result => result.map(|_| file),
+        }
+    }
+
+    /// Escapes a value, and additionally a leading `"` so that `quoted_values` does not strip it on load.
+    fn escape_value(&self, value: &str) -> String {
+        let mut escaped = Self::escape(&self.escape_env(value));
+        if self.options.strip_inline_comments {
+            escaped = strings::ReplaceAll(strings::ReplaceAll(&escaped, "#", "\\#"), ";", "\\;");
+        }
+        if self.options.block_values && strings::HasPrefix(&escaped, "<<") {
+            escaped.insert(0, '\\');
+        }
+        if self.options.quoted_values && strings::HasPrefix(&escaped, "\"") {
+            format!("\\{}", escaped)
+        } else {
+            escaped
+        }
+    }
+
+    /// Writes the keys accepted by `keep` in the configured file format, one line at a time.
+    fn write_lines(&self, w: &mut impl Write, keep: &dyn Fn(&str) -> bool) -> Result<(), Error> {
+        if self.options.trailing_newline {
+            self.write_entries(w, keep)
+        } else {
+            self.write_entries(
+                &mut HoldNewline {
+                    inner: w,
+                    newline: self.options.line_ending.as_str(),
+                    held: false,
+                },
+                keep,
+            )
+        }
+    }
+
+    fn write_entries(&self, w: &mut impl Write, keep: &dyn Fn(&str) -> bool) -> Result<(), Error> {
+        let object = self.read_table();
+        self.comment_lines(&object.header, w)?;
+        match self.options.format {
+            Format::Ini => self.ini_lines(&object, w, keep)?,
+            Format::Properties | Format::Env => {
+                for (k, v) in object.ordered(self.options.preserve_order) {
+                    if !keep(k) {
+                        continue;
+                    }
+                    self.comment_lines(object.comments(k), w)?;
+                    self.line(k, v, w)?;
+                }
+            }
+        }
+        self.comment_lines(&object.trailer, w)
+    }
+
+    /// Writes global keys first, then every section in the order its first key appears.
+    fn ini_lines(
+        &self,
+        object: &Table,
+        w: &mut impl Write,
+        keep: &dyn Fn(&str) -> bool,
+    ) -> Result<(), Error> {
+        let mut pairs = object.ordered(self.options.preserve_order);
+        pairs.retain(|(k, _)| keep(k));
+        let mut sections: Vec<&str> = vec![];
+        let mut has_global = false;
+        let delimiter = self.options.namespace_delimiter.to_string();
+        for (k, v) in pairs.iter() {
+            let (section, _, found) = strings::Cut(k, &delimiter);
+            if !found {
+                has_global = true;
+                self.comment_lines(object.comments(k), w)?;
+                self.line(k, v, w)?;
+            } else if !sections.contains(&section) {
+                sections.push(section);
+            }
+        }
+        for (i, section) in sections.iter().enumerate() {
+            match object.section_comments.get(&object.normalize(section)) {
+                Some(comments) => self.comment_lines(comments, w)?,
+                None if i > 0 || has_global => {
+                    w.write_all(self.options.line_ending.as_str().as_bytes())?
+                }
+                None => {}
+            }
+            write!(
+                w,
+                "[{}]{}",
+                self.cased_key(section),
+                self.options.line_ending.as_str()
+            )?;
+            for (k, v) in pairs.iter() {
+                let (s, key, found) = strings::Cut(k, &delimiter);
+                if found && s == *section {
+                    self.comment_lines(object.comments(k), w)?;
+                    self.line(key, v, w)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes `r` with the configured encoding and loads its lines.
+    fn load_with(
+        &mut self,
+        mut r: impl Read,
+        keep: &dyn Fn(&str) -> bool,
+        errors: Option<&mut Vec<(usize, String)>>,
+    ) -> Result<(), SettingsError> {
+        match self.options.encoding {
+            Encoding::Utf8 => self.load_lines(r, keep, errors),
+            encoding => {
+                let mut bytes = vec![];
+                r.read_to_end(&mut bytes)?;
+                let text = encoding.decode(&bytes)?;
+                self.load_lines(text.as_bytes(), keep, errors)
+            }
+        }
+    }
+
+    /// Reads UTF-8 lines, joining continuations and collecting comments, and sets the keys accepted by `keep`.
+    /// With `errors`, a line that does not parse is recorded there and skipped instead of ending the load.
+    fn load_lines(
+        &mut self,
+        r: impl Read,
+        keep: &dyn Fn(&str) -> bool,
+        mut errors: Option<&mut Vec<(usize, String)>>,
+    ) -> Result<(), SettingsError> {
+        let mut br = BufReader::new(r);
+        let mut line = String::new();
+        let mut logical = String::new();
+        let mut section = String::new();
+        let mut comments: Vec<String> = vec![];
+        let mut continued = false;
+        let mut line_no = 0;
+        let mut start_line = 0;
+        let mut seen = HashMap::new();
+        // header line, delimiter, body lines and line number of an open `<<DELIMITER` block
+        let mut block: Option<(String, String, Vec<String>, usize)> = None;
+        loop {
+            // room for the line ending, and for a byte order mark on the first line
+            let slack = if line_no == 0 { 5 } else { 2 };
+            let max = self
+                .options
+                .max_line_bytes
+                .map(|limit| (limit.saturating_sub(logical.len()) as u64).saturating_add(slack));
+            match Self::read_physical(&mut br, &mut line, max) {
+                Ok(i) => {
+                    if i == 0 {
+                        break;
+                    } else {
+                        line_no += 1;
+                        if !continued {
+                            start_line = line_no;
+                        }
+                        let mut physical = Self::trim_line_ending(&line);
+                        if line_no == 1 {
+                            physical = physical.strip_prefix('\u{feff}').unwrap_or(physical);
+                        }
+                        if let Some(limit) = self.options.max_line_bytes {
+                            // a read cut off at the cap is over the limit whatever is left after trimming
+                            let capped = max == Some(i as u64) && !line.ends_with('\n');
+                            if capped || logical.len() + physical.len() > limit {
+                                if capped {
+                                    Self::skip_line(&mut br)?;
+                                }
+                                let too_long = Err(SettingsError::Parse {
+                                    line: start_line,
+                                    msg: format!("line is longer than {} bytes", limit),
+                                });
+                                line.clear();
+                                logical.clear();
+                                continued = false;
+                                Self::recover(too_long, &mut errors)?;
+                                continue;
+                            }
+                        }
+                        if let Some((_, delimiter, body, _)) = block.as_mut() {
+                            let closed = strings::TrimSpace(physical) == delimiter.as_str();
+                            if !closed {
+                                body.push(physical.to_owned());
+                            }
+                            line.clear();
+                            let finished = if closed { block.take() } else { None };
+                            if let Some((header, _, body, header_line)) = finished {
+                                let parsed = self.parse_line(
+                                    &header,
+                                    header_line,
+                                    &mut section,
+                                    &mut seen,
+                                    Some(body.join("\n")),
+                                    keep,
+                                );
+                                if let Some(key) = Self::recover(parsed, &mut errors)? {
+                                    self.attach_comments(&key, &mut comments);
+                                }
+                            }
+                            continue;
+                        }
+                        if continued {
+                            logical.push_str(strings::TrimLeft(physical, " \t\x0c"));
+                        } else if !self.is_comment(strings::TrimSpace(physical)) {
+                            logical.push_str(physical);
+                        } else if self.options.preserve_comments {
+                            comments.push(physical.to_owned());
+                        }
+                        line.clear();
+                        continued =
+                            self.options.format != Format::Env && Self::is_continued(&logical);
+                        if continued {
+                            logical.pop();
+                        } else if let Some(delimiter) = self.block_delimiter(&logical) {
+                            block =
+                                Some((std::mem::take(&mut logical), delimiter, vec![], start_line));
+                        } else {
+                            let header = self
+                                .section_header(Self::trim_unescaped(&logical))
+                                .is_some();
+                            let parsed = self.parse_line(
+                                &logical,
+                                start_line,
+                                &mut section,
+                                &mut seen,
+                                None,
+                                keep,
+                            );
+                            if let Some(key) = Self::recover(parsed, &mut errors)? {
+                                self.attach_comments(&key, &mut comments);
+                            } else if header && !comments.is_empty() {
+                                let mut object = self.write_table();
+                                let section = object.normalize(&section);
+                                object
+                                    .section_comments
+                                    .insert(section, std::mem::take(&mut comments));
+                            }
+                            logical.clear();
+                        }
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        if let Some((_, delimiter, _, header_line)) = block {
+            let unclosed = Err(SettingsError::Parse {
+                line: header_line,
+                msg: format!("block is missing its closing {:?}", delimiter),
+            });
+            Self::recover(unclosed, &mut errors)?;
+        }
+        if continued {
+            let parsed = self.parse_line(&logical, start_line, &mut section, &mut seen, None, keep);
+            if let Some(key) = Self::recover(parsed, &mut errors)? {
+                self.attach_comments(&key, &mut comments);
+            }
+        }
+        if !comments.is_empty() {
+            self.write_table().trailer = comments;
+        }
+        Ok(())
+    }
+
+    /// Reads one line like `read_line`, but no more than `max` bytes of it when `max` is set.
+    fn read_physical(
+        br: &mut impl BufRead,
+        line: &mut String,
+        max: Option<u64>,
+    ) -> Result<usize, Error> {
+        let max = match max {
+            Some(max) => max,
+            None => return br.read_line(line),
+        };
+        let mut bytes = vec![];
+        let n = br.take(max).read_until(b'\n', &mut bytes)?;
+        match String::from_utf8(bytes) {
+            Ok(s) => line.push_str(&s),
+            // the limit may split a character; the line is over the limit then and is rejected by its length
+            Err(err) if n as u64 == max => line.push_str(&String::from_utf8_lossy(err.as_bytes())),
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                ))
+            }
+        }
+        Ok(n)
+    }
+
+    /// Discards the rest of the current line without holding it in memory.
+    fn skip_line(br: &mut impl BufRead) -> Result<(), Error> {
+        loop {
+            let buf = br.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(());
+            }
+            match buf.iter().position(|&b| b == b'\n') {
+                Some(end) => {
+                    br.consume(end + 1);
+                    return Ok(());
+                }
+                None => {
+                    let n = buf.len();
+                    br.consume(n);
+                }
+            }
+        }
+    }
+
+    /// Moves a parse error into `errors` when the load collects them, passing everything else through.
+    fn recover(
+        parsed: Result<Option<String>, SettingsError>,
+        errors: &mut Option<&mut Vec<(usize, String)>>,
+    ) -> Result<Option<String>, SettingsError> {
+        match (parsed, errors) {
+            (Err(SettingsError::Parse { line, msg }), Some(errors)) => {
+                errors.push((line, msg));
+                Ok(None)
+            }
+            (parsed, _) => parsed,
+        }
+    }
+
+    /// Returns the delimiter of a `key = <<DELIMITER` line that opens a block, when `block_values` is enabled.
+    fn block_delimiter(&self, line: &str) -> Option<String> {
+        if !self.options.block_values || self.is_comment(strings::TrimSpace(line)) {
+            return None;
+        }
+        let (_, value) = Self::cut_key_value(line);
+        let delimiter = strings::TrimSpace(value).strip_prefix("<<")?;
+        if !delimiter.is_empty()
+            && delimiter
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            Some(delimiter.to_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Parses one logical line, returning the key it set, if any.
+    /// A `block` replaces the value written on the line; keys rejected by `keep` are parsed but not set.
+    fn parse_line(
+        &mut self,
+        line: &str,
+        line_no: usize,
+        section: &mut String,
+        seen: &mut HashMap<String, String>,
+        block: Option<String>,
+        keep: &dyn Fn(&str) -> bool,
+    ) -> Result<Option<String>, SettingsError> {
+        let parse_err = |msg| SettingsError::Parse { line: line_no, msg };
+        let line_str = Self::trim_unescaped(line);
+        if self.is_comment(line_str) {
+            return Ok(None);
+        }
+        if let Some(name) = self.section_header(line_str) {
+            *section = name.to_owned();
+            return Ok(None);
+        }
+        let (mut key, mut value) = if self.options.format == Format::Env {
+            self.cut_env_line(line_str)
+        } else {
+            let (key, mut value) = Self::cut_key_value(line_str);
+            let quoted = self.quoted_value(value);
+            if quoted.is_none() && self.options.strip_inline_comments {
+                value = Self::strip_inline_comment(value);
+            }
+            let value = quoted.unwrap_or_else(|| Self::trim_unescaped(value));
+            (
+                Self::unescape(Self::trim_unescaped(key)).map_err(parse_err)?,
+                Self::unescape(value).map_err(parse_err)?,
+            )
+        };
+        if key.is_empty() {
+            return Ok(None);
+        }
+        if let Some(block) = block {
+            value = block;
+        }
+        if self.options.expand_env {
+            value = Self::expand_env(&value);
+        }
+        if !section.is_empty() {
+            key = format!("{}{}{}", section, self.options.namespace_delimiter, key);
+        }
+        let normalized = self.read_table().normalize(&key);
+        if let Some(first) = seen.get(&normalized) {
+            match self.options.on_duplicate {
+                DuplicatePolicy::Overwrite => {}
+                DuplicatePolicy::KeepFirst => return Ok(None),
+                DuplicatePolicy::Error if *first == key => {
+                    return Err(parse_err(format!("duplicate key {:?}", key)))
+                }
+                DuplicatePolicy::Error => {
+                    return Err(parse_err(format!(
+                        "key {:?} collides with {:?}",
+                        key, first
+                    )))
+                }
+            }
+        } else {
+            seen.insert(normalized, key.clone());
+        }
+        if !keep(&key) {
+            return Ok(Some(key));
+        }
+        self.set_checked(&key, &value, Source::File)
+            .map_err(|err| parse_err(err.to_string()))?;
+        Ok(Some(key))
+    }
+
+    /// Returns the name of an INI `[section]` header line.
+    fn section_header<'a>(&self, line: &'a str) -> Option<&'a str> {
+        if self.options.format == Format::Ini
+            && strings::HasPrefix(line, "[")
+            && strings::HasSuffix(line, "]")
+        {
+            Some(strings::TrimSpace(&line[1..line.len() - 1]))
+        } else {
+            None
+        }
+    }
+
+    /// Splits a `.env` line such as `export FOO="bar"`, removing the optional `export` and surrounding quotes.
+    fn cut_env_line(&self, line: &str) -> (String, String) {
+        let line = strings::TrimPrefix(line, "export ");
+        let (key, value, _) = strings::Cut(line, "=");
+        let value = strings::TrimSpace(value);
+        let quoted = |q: &str| {
+            value.len() >= 2 && strings::HasPrefix(value, q) && strings::HasSuffix(value, q)
+        };
+        let value = if quoted("\"") {
+            Self::unquote(&value[1..value.len() - 1])
+        } else if quoted("'") {
+            value[1..value.len() - 1].to_owned()
+        } else if self.options.strip_inline_comments {
+            strings::TrimSpace(Self::strip_inline_comment(value)).to_owned()
+        } else {
+            value.to_owned()
+        };
+        (strings::TrimSpace(key).to_owned(), value)
+    }
+
+    /// Decodes `\"`, `\\` and `\n` inside a double-quoted `.env` value; other backslashes are kept.
+    fn unquote(s: &str) -> String {
+        let mut out = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some(c @ '"') | Some(c @ '\\') => out.push(c),
+                Some(other) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => out.push('\\'),
+            }
+        }
+        out
+    }
+
+    /// Double-quotes a `.env` value when it would not be read back unchanged otherwise.
+    /// With `strip_inline_comments`, values containing `;` are quoted too, and with `block_values` those starting with `<<`.
+    fn quote(&self, value: &str) -> String {
+        let value = &self.escape_env(value);
+        let inline_comment = |c| self.options.strip_inline_comments && c == ';';
+        let block = self.options.block_values && strings::HasPrefix(value, "<<");
+        if !block
+            && !value
+                .chars()
+                .any(|c| c.is_whitespace() || "\"'\\#".contains(c) || inline_comment(c))
+        {
+            return value.to_owned();
+        }
+        let escaped = strings::ReplaceAll(strings::ReplaceAll(value, "\\", "\\\\"), "\"", "\\\"");
+        format!("\"{}\"", strings::ReplaceAll(escaped, "\n", "\\n"))
+    }
+
+    /// Attaches the pending comments to `key`, or drops them when `key` was filtered out.
+    fn attach_comments(&self, key: &str, comments: &mut Vec<String>) {
+        if !comments.is_empty() {
+            let mut object = self.write_table();
+            if object.contains_key(key) {
+                let key = object.normalize(key);
+                object.comments.insert(key, std::mem::take(comments));
+            } else {
+                comments.clear();
+            }
+        }
+    }
+
+    /// Turns each line of `text` into a `#` comment line.
+    fn comment_text(text: &str) -> Vec<String> {
+        text.split('\n').map(|line| format!("# {}", line)).collect()
+    }
+
+    fn comment_lines(&self, lines: &[String], w: &mut impl Write) -> Result<(), Error> {
+        for line in lines {
+            write!(w, "{}{}", line, self.options.line_ending.as_str())?;
+        }
+        Ok(())
+    }
+
+    /// With `expand_env`, writes `${` as `$${` so `load` reads it back literally instead of expanding it.
+    fn escape_env(&self, value: &str) -> String {
+        if self.options.expand_env {
+            strings::ReplaceAll(value, "${", "$${")
+        } else {
+            value.to_owned()
+        }
+    }
+
+    /// Replaces `${VAR}` with the value of the environment variable, keeping unset ones and turning `$${` into a literal `${`.
+    fn expand_env(value: &str) -> String {
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(i) = rest.find('$') {
+            out.push_str(&rest[..i]);
+            rest = &rest[i..];
+            if strings::HasPrefix(rest, "$${") {
+                out.push_str("${");
+                rest = &rest[3..];
+            } else if let (true, Some(end)) = (strings::HasPrefix(rest, "${"), rest.find('}')) {
+                match env::var(&rest[2..end]) {
+                    Ok(v) => out.push_str(&v),
+                    Err(_) => out.push_str(&rest[..=end]),
+                }
+                rest = &rest[end + 1..];
+            } else {
+                out.push('$');
+                rest = &rest[1..];
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Decodes the escape sequences `\t`, `\n`, `\r`, `\f`, `\uXXXX` and `\<char>` (for example `\\`, `\=` or `\:`).
+    fn unescape(s: &str) -> Result<String, String> {
+        let mut out = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('t') => out.push('\t'),
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some('f') => out.push('\x0c'),
+                Some('u') => {
+                    let mut unit = Self::hex4(&mut chars)?;
+                    if (0xD800..0xDC00).contains(&unit) {
+                        // a high surrogate must be followed by an escaped low surrogate
+                        if chars.next() != Some('\\') || chars.next() != Some('u') {
+                            return Err(Self::malformed_unicode());
+                        }
+                        let low = Self::hex4(&mut chars)?;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return Err(Self::malformed_unicode());
+                        }
+                        unit = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+                    }
+                    out.push(char::from_u32(unit).ok_or_else(Self::malformed_unicode)?);
+                }
+                Some(other) => out.push(other),
+                None => {}
+            }
+        }
+        Ok(out)
+    }
+
+    fn hex4(chars: &mut std::str::Chars) -> Result<u32, String> {
+        let mut unit = 0;
+        for _ in 0..4 {
+            let digit = chars
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(Self::malformed_unicode)?;
+            unit = unit * 16 + digit;
+        }
+        Ok(unit)
+    }
+
+    fn malformed_unicode() -> String {
+        "malformed \\uXXXX encoding".to_owned()
+    }
+
+    /// Applies the `key_case` option to a key or section name being written.
+    fn cased_key(&self, key: &str) -> String {
+        match self.options.key_case {
+            KeyCase::AsIs => key.to_owned(),
+            KeyCase::Upper => strings::ToUpper(key),
+            KeyCase::Lower => strings::ToLower(key),
+        }
+    }
+
+    /// Fails when a `.env` key is not a valid variable name, or when `key_case` would write two keys the same,
+    /// so one of the values would be lost on reload.
+    fn check_keys(&self, keep: &dyn Fn(&str) -> bool) -> Result<(), SettingsError> {
+        let env = self.options.format == Format::Env;
+        if !env && self.options.key_case == KeyCase::AsIs {
+            return Ok(());
+        }
+        let object = self.read_table();
+        let mut written: HashMap<String, &String> = HashMap::new();
+        for (k, _) in object.ordered(self.options.preserve_order) {
+            if !keep(k) {
+                continue;
+            }
+            if env && !Self::is_env_name(&self.cased_key(k)) {
+                return Err(SettingsError::Invalid {
+                    key: k.to_owned(),
+                    msg: "is not a valid environment variable name".to_owned(),
+                });
+            }
+            if let Some(first) = written.insert(self.cased_key(k), k) {
+                return Err(SettingsError::Invalid {
+                    key: k.to_owned(),
+                    msg: format!("is written the same as {:?}", first),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports whether `key` is made of ASCII letters, digits, `_` and `.`, and does not start with a digit.
+    fn is_env_name(key: &str) -> bool {
+        !key.is_empty()
+            && !key.starts_with(|c: char| c.is_ascii_digit())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    }
+
+    /// Escapes a key like a value, and additionally the separators and a leading comment marker.
+    fn escape_key(&self, key: &str) -> String {
+        let mut out = strings::ReplaceAll(
+            strings::ReplaceAll(Self::escape(key), "=", "\\="),
+            ":",
+            "\\:",
+        );
+        if strings::HasPrefix(&out, "#") || strings::HasPrefix(&out, "!") || self.is_comment(&out) {
+            out.insert(0, '\\');
+        }
+        out
+    }
+
+    /// Escapes backslashes and control characters so the value is read back unchanged by `load`.
+    /// Leading and trailing whitespace is escaped as well, spaces as `\ ` and other whitespace as `\uXXXX`,
+    /// so it is not trimmed away by `load`.
+    fn escape(s: &str) -> String {
+        let mut out = String::with_capacity(s.len());
+        let body = s.trim_start();
+        let trimmed = body.trim_end();
+        Self::escape_padding(&s[..s.len() - body.len()], &mut out);
+        for c in trimmed.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\t' => out.push_str("\\t"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\x0c' => out.push_str("\\f"),
+                _ => out.push(c),
+            }
+        }
+        Self::escape_padding(&body[trimmed.len()..], &mut out);
+        out
+    }
+
+    /// Escapes every whitespace character of leading or trailing padding.
+    fn escape_padding(pad: &str, out: &mut String) {
+        for c in pad.chars() {
+            match c {
+                ' ' => out.push_str("\\ "),
+                '\t' => out.push_str("\\t"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\x0c' => out.push_str("\\f"),
+                // every Unicode whitespace character is in the Basic Multilingual Plane
+                _ => out.push_str(&format!("\\u{:04x}", c as u32)),
+            }
+        }
+    }
+
+    /// Trims surrounding whitespace, keeping trailing whitespace that is escaped with a backslash.
+    fn trim_unescaped(s: &str) -> &str {
+        let s = s.trim_start();
+        let mut end = s.len();
+        for (i, c) in s.char_indices().rev() {
+            if !c.is_whitespace() || Self::is_continued(&s[..i]) {
+                break;
+            }
+            end = i;
+        }
+        &s[..end]
+    }
+
+    /// Removes one trailing `\n`, `\r\n` or `\r`, so Windows and Unix files read the same.
+    fn trim_line_ending(line: &str) -> &str {
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        line.strip_suffix('\r').unwrap_or(line)
+    }
+
+    /// Reports whether a line ends with an odd number of backslashes, i.e. continues on the next line.
+    fn is_continued(line: &str) -> bool {
+        line.bytes().rev().take_while(|b| *b == b'\\').count() % 2 == 1
+    }
+
+    /// Returns the text between the quotes of a value written as `"..."`, when `quoted_values` is enabled.
+    /// Only whitespace, or an inline comment when those are stripped, may follow the closing quote.
+    fn quoted_value<'a>(&self, value: &'a str) -> Option<&'a str> {
+        if !self.options.quoted_values {
+            return None;
+        }
+        let rest = strings::TrimSpace(value).strip_prefix('"')?;
+        let mut escaped = false;
+        for (i, c) in rest.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    let mut tail = &rest[i + 1..];
+                    if self.options.strip_inline_comments {
+                        tail = Self::strip_inline_comment(tail);
+                    }
+                    return strings::TrimSpace(tail).is_empty().then(|| &rest[..i]);
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Cuts a value at the first unescaped `#` or `;`.
+    fn strip_inline_comment(value: &str) -> &str {
+        let mut escaped = false;
+        for (i, c) in value.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '#' | ';' => return &value[..i],
+                _ => {}
+            }
+        }
+        value
+    }
+
+    /// Splits a line at the first unescaped `=` or `:`.
+    fn cut_key_value(line: &str) -> (&str, &str) {
+        let mut escaped = false;
+        for (i, c) in line.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '=' | ':' => return (&line[..i], &line[i + 1..]),
+                _ => {}
+            }
+        }
+        // a line without a separator is a key with an empty value
+        (line, "")
+    }
+
+    /// Blank lines and lines starting with one of the comment prefixes, or with `;` in INI files.
+    fn is_comment(&self, line: &str) -> bool {
+        if line.is_empty() {
+            return true;
+        }
+        if self
+            .options
+            .comment_prefixes
+            .iter()
+            .any(|prefix| strings::HasPrefix(line, prefix))
+        {
+            return true;
+        }
+        self.options.format == Format::Ini && strings::HasPrefix(line, ";")
+    }
+}
+
+impl Settings for Properties {
+    fn property(&self, key: &str) -> Option<String> {
+        self.read_table().get(key).map(|v| v.to_owned())
+    }
+
+    fn property_slice(&self, key: &str) -> Option<Vec<String>> {
+        self.property_slice_with(key, &self.options.list_separator.to_string())
+    }
+
+    fn set_property_slice(&mut self, key: &str, values: Vec<String>) {
+        let sep = self.options.list_separator.to_string();
+        self.set_property_slice_with(key, values, &sep);
+    }
+
+    fn set_property(&mut self, key: &str, value: &str) {
+        let _ = self.try_set_property(key, value);
+    }
+
+    fn load(&mut self, r: impl Read) -> Result<(), SettingsError> {
+        self.load_filtered(r, |_| true)
+    }
+
+    fn load_from_file(&mut self, file_path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        let f = fs::File::open(file_path)?;
+        self.load(f)
+    }
+
+    fn store(&self, w: impl Write) -> Result<(), SettingsError> {
+        self.check_keys(&|_| true)?;
+        let mut w = BufWriter::new(w);
+        self.write_lines(&mut w, &|_| true)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    fn store_to_file(&self, file_path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        // replace the file a symbolic link points to rather than the link itself
+        let resolved = fs::canonicalize(&file_path).ok();
+        let path = resolved.as_deref().unwrap_or_else(|| file_path.as_ref());
+        let name = path
+            .file_name()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "file path has no file name"))?;
+        // unique per call, so concurrent stores from threads of this process never share a temp file
+        let tmp = path.with_file_name(format!(
+            ".{}.{}.{}.tmp",
+            name.to_string_lossy(),
+            process::id(),
+            TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = (|| -> Result<(), SettingsError> {
+            let mut f = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&tmp)?;
+            if let Ok(meta) = fs::metadata(path) {
+                f.set_permissions(meta.permissions())?;
+            }
+            self.store(&mut f)?;
+            f.sync_all()?;
+            fs::rename(&tmp, path)?;
+            Ok(())
+        })();
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        written
+    }
+
+    fn property_names(&self) -> Vec<String> {
+        let mut names = self.read_table().keys.clone();
+        if !self.options.preserve_order {
+            names.sort();
+        }
+        names
+    }
+
+    fn remove_property(&mut self, key: &str) -> Option<String> {
+        self.write_table().remove(key)
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.read_table().contains_key(key)
+    }
+
+    fn len(&self) -> usize {
+        self.read_table().len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.read_table().is_empty()
+    }
+
+    fn property_int(&self, key: &str) -> Option<i64> {
+        self.property(key)
+            .and_then(|v| strings::TrimSpace(&v).parse().ok())
+    }
+
+    fn property_bool(&self, key: &str) -> Option<bool> {
+        self.property(key).and_then(
+            |v| match strings::ToLower(strings::TrimSpace(&v)).as_str() {
+                "true" | "yes" | "on" | "1" => Some(true),
+                "false" | "no" | "off" | "0" => Some(false),
+                _ => None,
+            },
+        )
+    }
+
+    fn property_float(&self, key: &str) -> Option<f64> {
+        self.property(key)
+            .and_then(|v| f64::from_str(strings::TrimSpace(&v)).ok())
+    }
+
+    fn iter(&self) -> Vec<(String, String)> {
+        let object = self.read_table();
+        object
+            .ordered(self.options.preserve_order)
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    }
+
+    fn merge(&mut self, other: &impl Settings) {
+        let pairs: Vec<_> = other
+            .iter()
+            .into_iter()
+            .map(|(k, v)| {
+                let source = other.source_of(&k).unwrap_or(Source::Runtime);
+                (k, v, source)
+            })
+            .collect();
+        let mut object = self.write_table();
+        pairs
+            .iter()
+            .filter(|(k, v, _)| self.validate(k, v).is_ok())
+            .for_each(|(k, v, source)| object.insert(k, v, *source));
+    }
+
+    fn property_or(&self, key: &str, default: &str) -> String {
+        self.property(key).unwrap_or_else(|| default.to_owned())
+    }
+
+    fn property_slice_or(&self, key: &str, default: Vec<String>) -> Vec<String> {
+        self.property_slice(key).unwrap_or(default)
+    }
+
+    fn store_json(&self, mut w: impl Write) -> Result<(), SettingsError> {
+        w.write_all(json::write_object(&self.iter()).as_bytes())?;
+        Ok(())
+    }
+
+    fn load_json(&mut self, mut r: impl Read) -> Result<(), SettingsError> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        let pairs =
+            json::parse_object(&text).map_err(|(line, msg)| SettingsError::Parse { line, msg })?;
+        for (k, v) in pairs.iter() {
+            self.validate(k, v)?;
+        }
+        let mut object = self.write_table();
+        pairs
+            .iter()
+            .for_each(|(k, v)| object.insert(k, v, Source::File));
+        Ok(())
+    }
+
+    fn store_toml(&self, mut w: impl Write) -> Result<(), SettingsError> {
+        w.write_all(toml::write_document(&self.iter()).as_bytes())?;
+        Ok(())
+    }
+
+    fn load_from_str(&mut self, s: &str) -> Result<(), SettingsError> {
+        self.load(s.as_bytes())
+    }
+
+    fn load_from_bytes(&mut self, b: &[u8]) -> Result<(), SettingsError> {
+        self.load(b)
+    }
+
+    fn store_to_string(&self) -> Result<String, SettingsError> {
+        self.check_keys(&|_| true)?;
+        let mut buf = Vec::new();
+        self.write_lines(&mut buf, &|_| true)?;
+        String::from_utf8(buf).map_err(|err| Error::new(ErrorKind::InvalidData, err).into())
+    }
+
+    fn clear(&mut self) {
+        self.write_table().clear()
+    }
+
+    fn property_or_insert(&mut self, key: &str, default: &str) -> String {
+        let mut object = self.write_table();
+        if let Some(value) = object.get(key) {
+            return value.to_owned();
+        }
+        if self.validate(key, default).is_ok() {
+            object.insert(key, default, Source::Default);
+        }
+        default.to_owned()
+    }
+
+    fn set_properties(&mut self, pairs: impl IntoIterator<Item = (String, String)>) {
+        let mut object = self.write_table();
+        pairs
+            .into_iter()
+            .filter(|(k, v)| self.validate(k, v).is_ok())
+            .for_each(|(k, v)| object.insert(&k, &v, Source::Runtime));
+    }
+
+    fn properties_with_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        let object = self.read_table();
+        let prefix = object.normalize(prefix);
+        object
+            .ordered(self.options.preserve_order)
+            .into_iter()
+            .filter(|(k, _)| strings::HasPrefix(object.normalize(k), &prefix))
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect()
+    }
+
+    fn sub(&self, prefix: &str) -> Properties {
+        let head = format!("{}{}", prefix, self.options.namespace_delimiter);
+        let skip = head.chars().count();
+        let mut sub = SettingsBuilder {
+            options: self.options.clone(),
+        }
+        .build_properties();
+        sub.set_properties(
+            self.properties_with_prefix(&head)
+                .into_iter()
+                .map(|(k, v)| (k.chars().skip(skip).collect(), v)),
+        );
+        sub
+    }
+
+    fn values(&self) -> Vec<String> {
+        self.read_table()
+            .ordered(self.options.preserve_order)
+            .into_iter()
+            .map(|(_, v)| v.to_owned())
+            .collect()
+    }
+
+    fn update_file(&self, file_path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        let mut file = self.load_existing(&file_path, true)?;
+        file.merge(self);
+        file.store_to_file(file_path)
+    }
+
+    fn diff_file(&self, file_path: impl AsRef<Path>) -> Result<Vec<Change>, SettingsError> {
+        let file = self.load_existing(file_path, false)?;
+        let mut changes = vec![];
+        for (key, new) in self.iter() {
+            match file.property(&key) {
+                None => changes.push(Change::Added { key, value: new }),
+                Some(old) if old != new => changes.push(Change::Changed { key, old, new }),
+                Some(_) => {}
+            }
+        }
+        for (key, value) in file.iter() {
+            if !self.contains_key(&key) {
+                changes.push(Change::Removed { key, value });
+            }
+        }
+        changes.sort_by(|a, b| a.key().cmp(b.key()));
+        Ok(changes)
+    }
+
+    fn try_set_property(&mut self, key: &str, value: &str) -> Result<(), SettingsError> {
+        self.set_checked(key, value, Source::Runtime)
+    }
+
+    fn property_slice_with(&self, key: &str, sep: &str) -> Option<Vec<String>> {
+        self.read_table()
+            .get(key)
+            .map(|value| Self::split_list(value, sep))
+    }
+
+    fn set_property_slice_with(&mut self, key: &str, values: Vec<String>, sep: &str) {
+        self.set_property(key, &Self::join_list(&values, sep));
+    }
+
+    fn source_of(&self, key: &str) -> Option<Source> {
+        let object = self.read_table();
+        object.sources.get(&object.normalize(key)).copied()
+    }
+
+    fn load_layered<P: AsRef<Path>>(
+        &mut self,
+        files: &[P],
+        skip_missing: bool,
+    ) -> Result<(), SettingsError> {
+        for file in files {
+            let path = file.as_ref();
+            match self.load_from_file(path) {
+                Err(SettingsError::Io(err))
+                    if skip_missing && err.kind() == ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(SettingsError::File {
+                        path: path.to_path_buf(),
+                        source: Box::new(err),
+                    })
+                }
+                Ok(()) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<(), SettingsError> {
+        let mut files = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension() == Some("properties".as_ref()) {
+                files.push(path);
+            }
+        }
+        files.sort();
+        self.load_layered(&files, false)
+    }
+
+    fn rename_key(&mut self, old: &str, new: &str) -> bool {
+        self.write_table().rename(old, new)
+    }
+
+    fn rename_prefix(&mut self, old_prefix: &str, new_prefix: &str) -> usize {
+        self.write_table().rename_prefix(old_prefix, new_prefix)
+    }
+
+    fn load_filtered(
+        &mut self,
+        r: impl Read,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<(), SettingsError> {
+        self.load_with(r, &keep, None)
+    }
+
+    fn set_comment(&mut self, key: &str, comment: &str) {
+        let mut object = self.write_table();
+        if !object.contains_key(key) {
+            return;
+        }
+        let key = object.normalize(key);
+        if comment.is_empty() {
+            object.comments.remove(&key);
+        } else {
+            object.comments.insert(key, Self::comment_text(comment));
+        }
+    }
+
+    fn comment(&self, key: &str) -> Option<String> {
+        let object = self.read_table();
+        let lines: Vec<&str> = object
+            .comments(key)
+            .iter()
+            .map(|line| strings::TrimSpace(line))
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let ini = self.options.format == Format::Ini && strings::HasPrefix(line, ";");
+                let prefix = self
+                    .options
+                    .comment_prefixes
+                    .iter()
+                    .find(|prefix| strings::HasPrefix(line, prefix))
+                    .map_or(if ini { 1 } else { 0 }, |prefix| prefix.len());
+                let text = &line[prefix..];
+                text.strip_prefix(' ').unwrap_or(text)
+            })
+            .collect();
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
+        }
+    }
+
+    fn set_header(&mut self, text: &str) {
+        self.write_table().header = if text.is_empty() {
+            vec![]
+        } else {
+            Self::comment_text(text)
+        };
+    }
+
+    fn try_property_int(&self, key: &str) -> Result<i64, SettingsError> {
+        let value = self.property(key).ok_or_else(|| SettingsError::Missing {
+            key: key.to_owned(),
+        })?;
+        strings::TrimSpace(&value)
+            .parse()
+            .map_err(|_| SettingsError::Invalid {
+                key: key.to_owned(),
+                msg: format!("{:?} is not an integer", value),
+            })
+    }
+
+    fn property_duration(&self, key: &str) -> Option<Duration> {
+        let value = self.property(key)?;
+        let (n, unit) = Self::split_unit(&value)?;
+        let millis = match unit {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            "d" => 24 * 60 * 60 * 1000,
+            _ => return None,
+        };
+        n.checked_mul(millis).map(Duration::from_millis)
+    }
+
+    fn property_bytes(&self, key: &str) -> Option<u64> {
+        let value = self.property(key)?;
+        let (n, unit) = Self::split_unit(&value)?;
+        let scale: u64 = match unit {
+            "" | "B" => 1,
+            "KB" => 1000,
+            "MB" => 1000 * 1000,
+            "GB" => 1000 * 1000 * 1000,
+            "KiB" => 1 << 10,
+            "MiB" => 1 << 20,
+            "GiB" => 1 << 30,
+            _ => return None,
+        };
+        n.checked_mul(scale)
+    }
+
+    fn into_shared(self) -> Arc<Properties> {
+        Arc::new(self)
+    }
+
+    fn load_lenient(&mut self, r: impl Read) -> Result<(), Vec<(usize, String)>> {
+        let mut errors = vec![];
+        if let Err(err) = self.load_with(r, &|_| true, Some(&mut errors)) {
+            errors.push((0, err.to_string()));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn with_map<R>(&self, f: impl FnOnce(&HashMap<String, String>) -> R) -> R {
+        f(&self.read_table().map)
+    }
+
+    fn property_char(&self, key: &str) -> Option<char> {
+        let value = self.property(key)?;
+        let mut chars = strings::TrimSpace(&value).chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn property_indexed(&self, base: &str) -> Vec<String> {
+        let object = self.read_table();
+        let head = object.normalize(&format!("{}[", base));
+        let mut items: Vec<(usize, &String)> = object
+            .map
+            .iter()
+            .filter_map(|(k, v)| {
+                let index = k.strip_prefix(&head)?.strip_suffix(']')?;
+                if index.bytes().all(|b| b.is_ascii_digit()) {
+                    Some((index.parse().ok()?, v))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        items.sort();
+        items.into_iter().map(|(_, v)| v.to_owned()).collect()
+    }
+
+    fn reload_from_file(&mut self, file_path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        let mut fresh = SettingsBuilder {
+            options: self.options.clone(),
+        }
+        .build_properties();
+        fresh.load_from_file(file_path)?;
+        *self.write_table() = fresh
+            .object
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        Ok(())
+    }
+
+    fn property_map(&self, key: &str) -> Option<HashMap<String, String>> {
+        let entries = self.property_slice(key)?;
+        Some(
+            entries
+                .iter()
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| {
+                    let (k, v, _) = strings::Cut(entry, ":");
+                    (
+                        strings::TrimSpace(k).to_owned(),
+                        strings::TrimSpace(v).to_owned(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn store_filtered(
+        &self,
+        w: impl Write,
+        keep: impl Fn(&str) -> bool,
+    ) -> Result<(), SettingsError> {
+        self.check_keys(&keep)?;
+        let mut w = BufWriter::new(w);
+        self.write_lines(&mut w, &keep)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    fn entry(&mut self, key: &str) -> Entry<'_> {
+        Entry {
+            object: self.write_table(),
+            key: key.to_owned(),
+            validator: self.options.validator,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Accepts at most three bytes per call.
+    struct Chunked(Vec<u8>);
+
+    impl Write for Chunked {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+            let n = buf.len().min(3);
+            self.0.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    fn many_keys() -> Properties {
+        let mut p = builder().file_type_properties().build_properties();
+        for i in 0..5000 {
+            p.set_property(&format!("key.{}", i), &i.to_string());
+        }
+        p
+    }
+
+    #[test]
+    fn store_writes_every_line_of_a_large_list() {
+        let p = many_keys();
+        let text = p.store_to_string().unwrap();
+        assert_eq!(text.lines().count(), 5000);
+        for i in 0..5000 {
+            assert!(text.contains(&format!("key.{} = {}\n", i, i)));
+        }
+    }
+
+    #[test]
+    fn store_handles_short_writes() {
+        let p = many_keys();
+        let mut chunked = Chunked(Vec::new());
+        p.store(&mut chunked).unwrap();
+        assert_eq!(
+            String::from_utf8(chunked.0).unwrap(),
+            p.store_to_string().unwrap()
+        );
+    }
+
+    #[test]
+    fn concurrent_stores_to_one_file_never_mix() {
+        let path = env::temp_dir().join("gostd_settings_concurrent_store.properties");
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let mut p = builder().file_type_properties().build_properties();
+                    for n in 0..200 {
+                        p.set_property(&format!("key.{}", n), &i.to_string());
+                    }
+                    for _ in 0..10 {
+                        p.store_to_file(&path).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        let mut p = builder().file_type_properties().build_properties();
+        p.load_from_file(&path).unwrap();
+        assert_eq!(p.len(), 200);
+        let values: std::collections::HashSet<_> = p.values().into_iter().collect();
+        assert_eq!(values.len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn store_to_file_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = env::temp_dir().join("gostd_settings_store_permissions.properties");
+        fs::write(&path, "Password = old\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        let mut p = builder().file_type_properties().build_properties();
+        p.set_property("Password", "new");
+        p.update_file(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn store_to_file_follows_symlinks() {
+        let dir = env::temp_dir().join("gostd_settings_store_symlink");
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("app.properties");
+        let link = dir.join("current.properties");
+        fs::write(&target, "HttpPort = 8081\n").unwrap();
+        let _ = fs::remove_file(&link);
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let mut p = builder().file_type_properties().build_properties();
+        p.set_property("HttpPort", "8082");
+        p.store_to_file(&link).unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "HttpPort = 8082\n");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn env_block_marker_round_trips() {
+        let mut p = builder().file_type_env().block_values().build_properties();
+        p.set_property("MARKER", "<<EOF");
+        let text = p.store_to_string().unwrap();
+        assert_eq!(text, "MARKER=\"<<EOF\"\n");
+        let mut q = builder().file_type_env().block_values().build_properties();
+        q.load_from_str(&text).unwrap();
+        assert_eq!(q.property("MARKER").as_deref(), Some("<<EOF"));
+    }
+
+    #[test]
+    fn unicode_whitespace_padding_round_trips() {
+        let value = "\u{a0}\u{3000} Debug\u{2003}\u{b}\t ";
+        let mut p = builder().file_type_properties().build_properties();
+        p.set_property("LogLevel", value);
+        let text = p.store_to_string().unwrap();
+        assert_eq!(
+            text,
+            "LogLevel = \\u00a0\\u3000\\ Debug\\u2003\\u000b\\t\\ \n"
+        );
+        let mut q = builder().file_type_properties().build_properties();
+        q.load_from_str(&text).unwrap();
+        assert_eq!(q.property("LogLevel").as_deref(), Some(value));
+    }
+
+    #[test]
+    fn env_store_rejects_keys_that_cannot_be_read_back() {
+        for key in ["A=B", "MY KEY", "1ST", "", "#KEY"] {
+            let mut p = builder().file_type_env().build_properties();
+            p.set_property("OK", "1");
+            p.set_property(key, "x");
+            match p.store_to_string() {
+                Err(SettingsError::Invalid { key: k, .. }) => assert_eq!(k, key),
+                other => panic!("{:?} stored as {:?}", key, other),
+            }
+            assert!(p.store(Vec::new()).is_err());
+            assert!(p.store_filtered(Vec::new(), |k| k == "OK").is_ok());
+        }
+        let mut p = builder().file_type_env().build_properties();
+        p.set_property("app.DB_HOST_2", "10.11.1.5");
+        assert_eq!(p.store_to_string().unwrap(), "app.DB_HOST_2=10.11.1.5\n");
+    }
+
+    #[test]
+    fn panic_under_the_write_lock_does_not_make_the_list_unusable() {
+        let mut p = builder().file_type_properties().build_properties();
+        p.set_property("HttpPort", "8081");
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            p.entry("HttpPort").and_modify(|_| panic!("modify failed"));
+        }));
+        assert!(result.is_err());
+        assert!(p.object.is_poisoned());
+        assert_eq!(p.property("HttpPort").as_deref(), Some("8081"));
+        p.set_property("LogLevel", "Debug");
+        assert_eq!(p.property("LogLevel").as_deref(), Some("Debug"));
+        assert_eq!(
+            p.store_to_string().unwrap(),
+            "HttpPort = 8081\nLogLevel = Debug\n"
+        );
     }
 }
@@ -0,0 +1,42 @@
+//! Writer for flat TOML documents of string values.
+
+/// Formats the pairs as top-level TOML keys with basic string values.
+pub(crate) fn write_document(pairs: &[(String, String)]) -> String {
+    let mut out = String::new();
+    for (k, v) in pairs {
+        write_key(k, &mut out);
+        out.push_str(" = ");
+        write_string(v, &mut out);
+        out.push('\n');
+    }
+    out
+}
+
+/// Writes a bare key when possible; keys with dots or other characters are quoted so they are not read as nested tables.
+fn write_key(key: &str, out: &mut String) {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        out.push_str(key);
+    } else {
+        write_string(key, out);
+    }
+}
+
+fn write_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\x7f' => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,176 @@
+//! Reloading a property file in the background when it changes, behind the `watch` feature.
+use crate::{Properties, Settings, SettingsBuilder, SettingsError};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs;
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long the file must stay quiet after an event before it is reloaded.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Stops the watcher started by [`SettingsBuilder::watch_file`] when dropped.
+/// <details class="rustdoc-toggle top-doc">
+/// <summary class="docblock">zh-cn</summary>
+/// 由[`SettingsBuilder::watch_file`]启动的监视器，被丢弃时停止监视。
+/// </details>
+pub struct WatchHandle {
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WatchHandle {
+    /// Stops watching and waits for the background thread to finish.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 停止监视并等待后台线程结束。
+    /// </details>
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        // dropping the watcher closes the event channel, which ends the thread
+        self.watcher.take();
+        if let Some(thread) = self.thread.take() {
+            // a panicking callback already ended the thread, nothing is left to clean up
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn watch_error(err: notify::Error) -> SettingsError {
+    match err.kind {
+        notify::ErrorKind::Io(err) => SettingsError::Io(err),
+        _ => SettingsError::Io(Error::other(err.to_string())),
+    }
+}
+
+impl SettingsBuilder {
+    /// Loads the file at `file_path` with the builder's options and keeps watching it with the platform's
+    /// filesystem notifications (inotify, FSEvents, ReadDirectoryChangesW, ...) in a background thread.
+    /// Whenever the file changes it is reloaded into a fresh list and `on_reload` is called with it.
+    /// The directory holding the file is watched, so files replaced by a rename, as editors and `store_to_file` do, are followed.
+    /// Rapid successive writes are debounced: the file is reloaded once no event has arrived for 200ms.
+    /// A change that fails to load is skipped and the next change is tried again.
+    /// Returns the error of the first load or of starting the watcher, or a [`WatchHandle`] that stops the watcher when dropped.
+    /// <details class="rustdoc-toggle top-doc">
+    /// <summary class="docblock">zh-cn</summary>
+    /// 使用构建器的选项加载`file_path`处的文件，并在后台线程中通过平台的文件系统通知（inotify、FSEvents、ReadDirectoryChangesW等）持续监视它。
+    /// 文件每次变更后都会重新加载到新的属性列表中，并以其调用`on_reload`。监视的是文件所在的目录，因此能跟踪像编辑器和`store_to_file`那样通过重命名替换的文件。
+    /// 快速连续的写入会被合并：200毫秒内没有新的事件时才重新加载。加载失败的变更会被跳过，等待下一次变更。
+    /// 返回首次加载或启动监视器时的错误，或者一个被丢弃时停止监视的[`WatchHandle`]。
+    /// </details>
+    ///
+    /// # Example
+    /// ```
+    /// use gostd_settings::{builder, Settings};
+    /// use std::sync::mpsc;
+    /// use std::time::Duration;
+    /// let dir = std::env::temp_dir().join("gostd_settings_watch_file");
+    /// std::fs::create_dir_all(&dir).unwrap();
+    /// let path = dir.join("app.properties");
+    /// std::fs::write(&path, "HttpPort = 8081\n").unwrap();
+    ///
+    /// let (tx, rx) = mpsc::channel();
+    /// let handle = builder()
+    ///     .file_type_properties()
+    ///     .watch_file(&path, move |p| {
+    ///         tx.send(p.property("HttpPort")).unwrap();
+    ///     })
+    ///     .unwrap();
+    ///
+    /// std::fs::write(&path, "HttpPort = 8082\n").unwrap();
+    /// std::fs::write(&path, "HttpPort = 8083\n").unwrap();
+    /// let port = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    /// assert_eq!(port.as_deref(), Some("8083"));
+    ///
+    /// // other files in the directory are ignored
+    /// std::fs::write(dir.join("other.properties"), "HttpPort = 1\n").unwrap();
+    /// assert!(rx.recv_timeout(Duration::from_millis(500)).is_err());
+    ///
+    /// // a file replaced by a rename is followed
+    /// let mut p = builder().file_type_properties().build();
+    /// p.set_property("HttpPort", "8084");
+    /// p.store_to_file(&path).unwrap();
+    /// let port = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    /// assert_eq!(port.as_deref(), Some("8084"));
+    ///
+    /// handle.stop();
+    /// std::fs::remove_dir_all(&dir).unwrap();
+    ///
+    /// assert!(builder().watch_file("no/such/file.properties", |_| {}).is_err());
+    /// ```
+    pub fn watch_file(
+        self,
+        file_path: impl AsRef<Path>,
+        on_reload: impl Fn(&Properties) + Send + 'static,
+    ) -> Result<WatchHandle, SettingsError> {
+        let options = self.options;
+        SettingsBuilder {
+            options: options.clone(),
+        }
+        .build_properties()
+        .load_from_file(&file_path)?;
+
+        let path = fs::canonicalize(&file_path)?;
+        let dir: PathBuf = path.parent().unwrap_or_else(|| Path::new("/")).to_owned();
+        let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+        let mut watcher = notify::recommended_watcher(tx).map_err(watch_error)?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        let thread = thread::spawn(move || {
+            // when the last event for the file arrived, if it has not been reloaded yet
+            let mut pending: Option<Instant> = None;
+            loop {
+                let event = match pending {
+                    Some(since) => {
+                        match rx
+                            .recv_timeout(DEBOUNCE.checked_sub(since.elapsed()).unwrap_or_default())
+                        {
+                            Ok(event) => event,
+                            Err(RecvTimeoutError::Timeout) => {
+                                pending = None;
+                                let mut p = SettingsBuilder {
+                                    options: options.clone(),
+                                }
+                                .build_properties();
+                                if p.load_from_file(&path).is_ok() {
+                                    on_reload(&p);
+                                }
+                                continue;
+                            }
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        }
+                    }
+                    None => match rx.recv() {
+                        Ok(event) => event,
+                        Err(_) => return,
+                    },
+                };
+                let touched = match event {
+                    Ok(event) => !event.kind.is_access() && event.paths.contains(&path),
+                    // events may have been dropped, so reload to be safe
+                    Err(_) => true,
+                };
+                if touched {
+                    pending = Some(Instant::now());
+                }
+            }
+        });
+        Ok(WatchHandle {
+            watcher: Some(watcher),
+            thread: Some(thread),
+        })
+    }
+}